@@ -0,0 +1,211 @@
+//! A chain of every adapter, from the outlet to the device
+
+use std::{collections::BTreeMap, error::Error, fmt, ops::RangeInclusive};
+
+use num_bigint::BigUint;
+
+use crate::combination_finder::Combinations;
+
+/// The rules for plugging adapters into each other
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainConfig {
+    /// How much higher an adapter's output can be than its input
+    pub step_window: RangeInclusive<usize>,
+    /// How much higher the device's built in adapter is than the highest adapter
+    pub device_offset: usize,
+}
+
+impl Default for ChainConfig {
+    /// The puzzle rules: 1 to 3 jolts per step, and the device is rated 3 jolts higher
+    fn default() -> ChainConfig {
+        ChainConfig {
+            step_window: 1..=3,
+            device_offset: 3,
+        }
+    }
+}
+
+/// Two neighbouring joltages in the chain that no adapter can bridge
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImpossibleGap {
+    /// The lower joltage
+    pub from: usize,
+    /// The next joltage up
+    pub to: usize,
+    /// The steps that are allowed
+    pub step_window: RangeInclusive<usize>,
+}
+
+impl fmt::Display for ImpossibleGap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Can't get from {} to {} jolts: a step of {} is outside {}..={}",
+            self.from,
+            self.to,
+            self.to - self.from,
+            self.step_window.start(),
+            self.step_window.end()
+        )
+    }
+}
+
+impl Error for ImpossibleGap {}
+
+/// Every adapter, sorted by joltage and checked to be connectable
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdapterChain {
+    config: ChainConfig,
+    /// The adapter joltages in order
+    adapters: Vec<usize>,
+}
+
+impl AdapterChain {
+    /// Sorts the adapters and checks that each one can plug into the one before it,
+    /// starting from the 0 jolt outlet and finishing with the device
+    pub fn new(
+        mut adapters: Vec<usize>,
+        config: ChainConfig,
+    ) -> Result<AdapterChain, ImpossibleGap> {
+        adapters.sort_unstable();
+        let device = adapters.last().cloned().unwrap_or(0) + config.device_offset;
+        let mut previous = 0;
+        for &adapter in adapters.iter().chain(Some(&device)) {
+            if !config.step_window.contains(&(adapter - previous)) {
+                return Err(ImpossibleGap {
+                    from: previous,
+                    to: adapter,
+                    step_window: config.step_window,
+                });
+            }
+            previous = adapter;
+        }
+        Ok(AdapterChain { config, adapters })
+    }
+
+    /// The adapter joltages, lowest first
+    pub fn adapters(&self) -> &[usize] {
+        &self.adapters
+    }
+
+    /// The joltage of the device's built in adapter
+    pub fn device_joltage(&self) -> usize {
+        self.adapters.last().cloned().unwrap_or(0) + self.config.device_offset
+    }
+
+    /// Counts how many times each jolt difference appears in the chain,
+    /// from the outlet through every adapter to the device
+    pub fn differences(&self) -> BTreeMap<usize, usize> {
+        let mut histogram = BTreeMap::new();
+        let mut previous = 0;
+        for joltage in self
+            .adapters
+            .iter()
+            .cloned()
+            .chain(Some(self.device_joltage()))
+        {
+            *histogram.entry(joltage - previous).or_insert(0) += 1;
+            previous = joltage;
+        }
+        histogram
+    }
+
+    /// Works out every way to connect some of the adapters between the outlet and the device
+    pub fn combinations(&self) -> Combinations {
+        Combinations::new(
+            &self.adapters,
+            self.config.step_window.clone(),
+            self.device_joltage(),
+        )
+    }
+
+    /// Returns the number of possible ways to connect some of the adapters
+    /// between the outlet and the device
+    pub fn count_arrangements(&self) -> BigUint {
+        self.combinations().count().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use num_bigint::BigUint;
+
+    use super::{AdapterChain, ChainConfig, ImpossibleGap};
+
+    fn easy() -> Vec<usize> {
+        vec![16, 10, 15, 5, 1, 11, 7, 19, 6, 12, 4]
+    }
+
+    #[test]
+    fn test_easy() {
+        let chain = AdapterChain::new(easy(), ChainConfig::default()).unwrap();
+        assert_eq!(chain.device_joltage(), 22);
+        let expected: BTreeMap<usize, usize> = vec![(1, 7), (3, 5)].into_iter().collect();
+        assert_eq!(chain.differences(), expected);
+    }
+
+    #[test]
+    fn test_longer() {
+        let input = vec![
+            28, 33, 18, 42, 31, 14, 46, 20, 48, 47, 24, 23, 49, 45, 19, 38, 39, 11, 1, 32, 25, 35,
+            8, 17, 7, 9, 4, 2, 34, 10, 3,
+        ];
+        let chain = AdapterChain::new(input, ChainConfig::default()).unwrap();
+        let differences = chain.differences();
+        assert_eq!(differences[&1] * differences[&3], 22 * 10);
+    }
+
+    #[test]
+    fn test_impossible_gap() {
+        let got = AdapterChain::new(vec![1, 2, 7, 8], ChainConfig::default());
+        let expected = Err(ImpossibleGap {
+            from: 2,
+            to: 7,
+            step_window: 1..=3,
+        });
+        assert_eq!(got, expected);
+    }
+
+    #[test]
+    fn test_impossible_device_gap() {
+        let config = ChainConfig {
+            step_window: 1..=3,
+            device_offset: 5,
+        };
+        let got = AdapterChain::new(vec![1, 2, 3], config.clone());
+        let expected = Err(ImpossibleGap {
+            from: 3,
+            to: 8,
+            step_window: 1..=3,
+        });
+        assert_eq!(got, expected);
+        // With no adapters, the device plugs straight into the outlet
+        let got = AdapterChain::new(vec![], config);
+        let expected = Err(ImpossibleGap {
+            from: 0,
+            to: 5,
+            step_window: 1..=3,
+        });
+        assert_eq!(got, expected);
+        assert!(AdapterChain::new(vec![], ChainConfig::default()).is_ok());
+    }
+
+    #[test]
+    fn test_wider_window() {
+        let config = ChainConfig {
+            step_window: 1..=5,
+            device_offset: 1,
+        };
+        let chain = AdapterChain::new(vec![1, 2, 7, 8], config).unwrap();
+        assert_eq!(chain.device_joltage(), 9);
+        let expected: BTreeMap<usize, usize> = vec![(1, 4), (5, 1)].into_iter().collect();
+        assert_eq!(chain.differences(), expected);
+        // 1 is optional, and the device (9 jolts) can plug into 7 or 8
+        assert_eq!(chain.count_arrangements(), BigUint::from(4u8));
+        let got: Vec<Vec<usize>> = chain.combinations().arrangements().collect();
+        let expected = vec![vec![1, 2, 7, 8], vec![1, 2, 7], vec![2, 7, 8], vec![2, 7]];
+        assert_eq!(got, expected);
+    }
+}
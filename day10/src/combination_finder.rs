@@ -1,59 +1,195 @@
-use std::collections::HashMap;
+//! Counts and lists the ways adapters can be connected
+//!
+//! Every joltage (the outlet, each adapter, and the device) is a node. An adapter can plug into
+//! any lower node that is within the step window. We count the number of paths from each node to
+//! the device, working backwards from the device, so it's one pass with no recursion.
 
-/// Takes a step towards solving a combination. Recursive function
+use std::ops::RangeInclusive;
+
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+
+/// The nodes and the number of ways to finish from each one
+pub struct Combinations {
+    /// The outlet (0), then every adapter sorted by joltage, then the device
+    nodes: Vec<usize>,
+    step_window: RangeInclusive<usize>,
+    /// ways[i] is the number of ways to get from nodes[i] to the device
+    ways: Vec<BigUint>,
+}
+
+impl Combinations {
+    /// # Arguments
+    ///
+    ///  * adapters: The adapters we have, sorted by joltage, all lower than `target`
+    ///  * step_window: How much each adapter (and the device) can step the joltage up
+    ///  * target: The joltage of the device
+    pub fn new(
+        adapters: &[usize],
+        step_window: RangeInclusive<usize>,
+        target: usize,
+    ) -> Combinations {
+        let mut nodes = Vec::with_capacity(adapters.len() + 2);
+        nodes.push(0);
+        nodes.extend_from_slice(adapters);
+        nodes.push(target);
+        let last = nodes.len() - 1;
+        let mut ways = vec![BigUint::zero(); nodes.len()];
+        ways[last] = BigUint::one();
+        for from in (0..last).rev() {
+            let mut count = BigUint::zero();
+            for to in from + 1..nodes.len() {
+                let step = nodes[to] - nodes[from];
+                if step > *step_window.end() {
+                    break;
+                }
+                if step_window.contains(&step) {
+                    count += &ways[to];
+                }
+            }
+            ways[from] = count;
+        }
+        Combinations {
+            nodes,
+            step_window,
+            ways,
+        }
+    }
+
+    /// The total number of ways to get from the outlet to the device
+    pub fn count(&self) -> &BigUint {
+        &self.ways[0]
+    }
+
+    /// The nodes we can plug into `from`, that still have a way to reach the device
+    fn next_steps(&self, from: usize) -> impl Iterator<Item = usize> + '_ {
+        let start = self.nodes[from];
+        (from + 1..self.nodes.len())
+            .take_while(move |&to| self.nodes[to] - start <= *self.step_window.end())
+            .filter(move |&to| {
+                self.step_window.contains(&(self.nodes[to] - start)) && !self.ways[to].is_zero()
+            })
+    }
+
+    /// The adapter joltages used by a path of node indexes (leaving out the outlet and device)
+    fn adapters_in(&self, path: &[usize]) -> Vec<usize> {
+        let last = self.nodes.len() - 1;
+        path.iter()
+            .filter(|&&node| node != 0 && node != last)
+            .map(|&node| self.nodes[node])
+            .collect()
+    }
+
+    /// Returns the `index`th arrangement, in the same order as `arrangements()`, without
+    /// generating any of the ones before it. Handy for sampling
+    pub fn arrangement(&self, index: &BigUint) -> Option<Vec<usize>> {
+        if index >= self.count() {
+            return None;
+        }
+        let last = self.nodes.len() - 1;
+        let mut index = index.clone();
+        let mut path = vec![0];
+        let mut from = 0;
+        while from != last {
+            // Skip over whole branches until we find the one that holds `index`
+            for to in self.next_steps(from) {
+                if index < self.ways[to] {
+                    from = to;
+                    break;
+                }
+                index -= &self.ways[to];
+            }
+            path.push(from);
+        }
+        Some(self.adapters_in(&path))
+    }
+
+    /// Lazily lists every arrangement of adapters (lowest first), without the outlet or device
+    pub fn arrangements(&self) -> Arrangements<'_> {
+        let mut next: Vec<Box<dyn Iterator<Item = usize>>> = Vec::new();
+        if !self.ways[0].is_zero() {
+            next.push(Box::new(self.next_steps(0)));
+        }
+        Arrangements {
+            combinations: self,
+            path: vec![0],
+            next,
+        }
+    }
+}
+
+/// A depth first walk over every valid arrangement. Dead ends are never visited
+pub struct Arrangements<'a> {
+    combinations: &'a Combinations,
+    /// The node indexes we've plugged in so far
+    path: Vec<usize>,
+    /// For each node in `path`, the choices we haven't tried yet
+    next: Vec<Box<dyn Iterator<Item = usize> + 'a>>,
+}
+
+impl<'a> Iterator for Arrangements<'a> {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        let last = self.combinations.nodes.len() - 1;
+        while let Some(choices) = self.next.last_mut() {
+            match choices.next() {
+                Some(to) if to == last => {
+                    let mut path = self.path.clone();
+                    path.push(to);
+                    return Some(self.combinations.adapters_in(&path));
+                }
+                Some(to) => {
+                    self.path.push(to);
+                    self.next.push(Box::new(self.combinations.next_steps(to)));
+                }
+                None => {
+                    self.path.pop();
+                    self.next.pop();
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Returns the number of ways to get from the 0 jolt outlet to a device rated at `target`
 ///
 /// # Arguments
 ///
-///  * adapters: The list of adapters we have available, sorted by joltage
-///  * current_output: The output coming out of the socket (with all our adapters right now)
-///  * target_output: The output we hope to reach to power our laptop
-///
-/// Returns the number of combinations found that add up to the target_output
-fn step(
+///  * adapters: The adapters we have, sorted by joltage, all lower than `target`
+///  * step_window: How much each adapter (and the device) can step the joltage up
+///  * target: The joltage of the device
+pub fn count_combinations(
     adapters: &[usize],
-    current_output: usize,
-    target_output: usize,
-    mut cache: &mut HashMap<usize, usize>,
-) -> usize {
-    if let Some(count) = cache.get(&current_output) {
-        *count
-    } else if current_output == target_output {
-        // We've reached a valid combination, exit, and count it
-        // We've recursed down to the bottom
-        1
-    } else {
-        let count = adapters
-            .iter()
-            .cloned()
-            .enumerate()
-            .take_while(|(_i, adapter_output)| *adapter_output - current_output <= 3)
-            .map(|(i, next_step)| step(&adapters[i + 1..], next_step, target_output, &mut cache))
-            .sum();
-        cache.insert(current_output, count);
-        count
-    }
+    step_window: &RangeInclusive<usize>,
+    target: usize,
+) -> BigUint {
+    Combinations::new(adapters, step_window.clone(), target)
+        .count()
+        .clone()
 }
 
 /// Returns the number of possible combinations that you can connect your
 /// adapters to reach the target joltage
-pub fn find_combinations(mut adapters: Vec<usize>) -> usize {
+pub fn find_combinations(mut adapters: Vec<usize>) -> BigUint {
     adapters.sort();
-    // We don't add the +3 because we know if we reach the maximum adapter's output we've made a combination
-    let target = *adapters.last().unwrap_or(&0);
-    // Cache
-    let mut cache = HashMap::new();
-    // Count the combinations
-    step(&adapters, 0, target, &mut cache)
+    // The device is always rated 3 jolts higher than the biggest adapter
+    let target = adapters.last().unwrap_or(&0) + 3;
+    count_combinations(&adapters, &(1..=3), target)
 }
 
 #[cfg(test)]
 mod tests {
+    use num_bigint::BigUint;
+
+    use super::Combinations;
 
     #[test]
     fn test_easy() {
         let adapters = vec![16, 10, 15, 5, 1, 11, 7, 19, 6, 12, 4];
         let got = super::find_combinations(adapters);
-        let expected = 8;
+        let expected = BigUint::from(8u32);
         assert_eq!(expected, got)
     }
 
@@ -64,7 +200,53 @@ mod tests {
             8, 17, 7, 9, 4, 2, 34, 10, 3,
         ];
         let got = super::find_combinations(adapters);
-        let expected = 19208;
+        let expected = BigUint::from(19208u32);
         assert_eq!(expected, got)
     }
+
+    #[test]
+    fn test_no_overflow() {
+        // Every adapter from 1 to 300 gives a tribonacci number way past u64
+        let adapters: Vec<usize> = (1..=300).collect();
+        let got = super::find_combinations(adapters);
+        assert!(got > BigUint::from(u128::MAX));
+        // T(n) = T(n-1) + T(n-2) + T(n-3)
+        let mut t = vec![BigUint::from(1u8), BigUint::from(1u8), BigUint::from(2u8)];
+        for n in 3..=300 {
+            let next = &t[n - 1] + &t[n - 2] + &t[n - 3];
+            t.push(next);
+        }
+        assert_eq!(got, t[300]);
+    }
+
+    #[test]
+    fn test_arrangements() {
+        let adapters = vec![1, 4, 5, 6, 7, 10, 11, 12, 15, 16, 19];
+        let combinations = Combinations::new(&adapters, 1..=3, 22);
+        let got: Vec<Vec<usize>> = combinations.arrangements().collect();
+        assert_eq!(got.len(), 8);
+        assert_eq!(got[0], adapters);
+        assert_eq!(got[7], vec![1, 4, 7, 10, 12, 15, 16, 19]);
+        // Every arrangement is different, valid, and matches random access
+        for (index, arrangement) in got.iter().enumerate() {
+            assert_eq!(
+                combinations.arrangement(&BigUint::from(index)).as_ref(),
+                Some(arrangement)
+            );
+            let mut joltages = vec![0];
+            joltages.extend(arrangement);
+            joltages.push(22);
+            assert!(joltages
+                .windows(2)
+                .all(|pair| (1..=3).contains(&(pair[1] - pair[0]))));
+        }
+        assert_eq!(combinations.arrangement(&BigUint::from(8u8)), None);
+    }
+
+    #[test]
+    fn test_no_arrangements() {
+        let combinations = Combinations::new(&[1, 2, 9], 1..=3, 12);
+        assert_eq!(combinations.arrangements().next(), None);
+        assert_eq!(combinations.arrangement(&BigUint::from(0u8)), None);
+    }
 }
@@ -1,2 +1,5 @@
 mod combination_finder;
-pub use combination_finder::find_combinations;
+pub use combination_finder::{count_combinations, find_combinations, Arrangements, Combinations};
+
+mod adapter_chain;
+pub use adapter_chain::{AdapterChain, ChainConfig, ImpossibleGap};
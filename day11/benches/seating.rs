@@ -0,0 +1,75 @@
+//! Compares re-walking the rays every round (`Spaces::step_with`) with stepping over a
+//! precomputed visibility graph (`Simulation::step`) on big generated waiting rooms
+//!
+//! Run with `cargo bench`
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use day11::{Rule, Simulation, Spaces};
+
+/// Makes a `size` x `size` room where about 3 in 4 spaces are seats.
+/// Uses a tiny xorshift generator so every run gets the same room
+fn generate(size: usize) -> Spaces {
+    let mut state: u32 = 0x2545_f491;
+    let mut room = String::with_capacity(size * (size + 1));
+    for _y in 0..size {
+        for _x in 0..size {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            room.push(if state & 3 == 0 { '.' } else { 'L' });
+        }
+        room.push('\n');
+    }
+    room.parse().expect("Generated room should parse")
+}
+
+fn bench_step(c: &mut Criterion) {
+    let mut group = c.benchmark_group("part 2 round");
+    group.sample_size(20);
+    for &size in [100, 300].iter() {
+        // Get a few rounds in so there's a mix of full and empty seats
+        let rule = Rule::part2();
+        let spaces = generate(size).step_with(&rule).step_with(&rule);
+        group.bench_with_input(BenchmarkId::new("step_with", size), &spaces, |b, spaces| {
+            b.iter(|| black_box(spaces.step_with(&rule)))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("simulation", size),
+            &spaces,
+            |b, spaces| {
+                let mut simulation = Simulation::new(spaces, rule);
+                b.iter(|| black_box(simulation.step()))
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("build visibility graph", size),
+            &spaces,
+            |b, spaces| b.iter(|| black_box(Simulation::new(spaces, rule))),
+        );
+    }
+    group.finish();
+}
+
+fn bench_run(c: &mut Criterion) {
+    let mut group = c.benchmark_group("part 2 20 rounds");
+    group.sample_size(10);
+    let spaces = generate(200);
+    let rule = Rule::part2();
+    group.bench_function("step_with", |b| {
+        b.iter(|| {
+            let mut spaces = spaces.clone();
+            for _ in 0..20 {
+                spaces = spaces.step_with(&rule);
+            }
+            black_box(spaces)
+        })
+    });
+    group.bench_function("simulation", |b| {
+        b.iter(|| black_box(Simulation::new(&spaces, rule).run(Some(20))))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_step, bench_run);
+criterion_main!(benches);
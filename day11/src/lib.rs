@@ -1,4 +1,7 @@
 mod parser;
 
 mod model;
-pub use model::{Space, Spaces};
+pub use model::{
+    animate, export_frames, Frames, Layout, Neighbourhood, Outcome, Rule, Run, Simulation, Space,
+    Spaces, Topology, VisibilityGraph,
+};
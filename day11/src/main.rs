@@ -30,33 +30,74 @@ Each turn:
 Keep running turns / rounds until the state stabalizes. How many seats end up occupied ?
 */
 
-use std::fs::read_to_string;
+use std::{env::args, fs::read_to_string, io::stdout, path::Path};
 
-use day11::Spaces;
+use day11::{animate, export_frames, Outcome, Rule, Spaces};
+
+/// Stop drawing rooms that never settle down after this many rounds
+const FRAME_LIMIT: usize = 1000;
+
+/// Picks the rules from the part number on the command line (defaults to part 2)
+fn rule(part: Option<String>) -> Rule {
+    match part.as_deref() {
+        Some("1") => Rule::part1(),
+        _ => Rule::part2(),
+    }
+}
 
 fn main() {
     let input = read_to_string("input.txt").expect("Unable to read input.txt");
-    let mut spaces: Spaces = input.parse().expect("Unable to read input");
-    let mut next_step = spaces.step();
-    while spaces != next_step {
-        spaces = next_step;
-        next_step = spaces.step();
+    let spaces: Spaces = input.parse().expect("Unable to read input");
+    let mut args = args().skip(1);
+    match args.next().as_deref() {
+        // `day11 frames <dir> [part]` saves every round as a PPM image
+        Some("frames") => {
+            let dir = args.next().unwrap_or_else(|| "frames".to_string());
+            let rule = rule(args.next());
+            let count = export_frames(spaces.frames(&rule, Some(FRAME_LIMIT)), Path::new(&dir), 4)
+                .expect("Unable to write frames");
+            println!("Wrote {} frames to {}", count, dir);
+        }
+        // `day11 animate [fps] [part]` plays the rounds in the terminal
+        Some("animate") => {
+            let fps: f64 = match args.next() {
+                Some(fps) => match fps.parse() {
+                    Ok(fps) => fps,
+                    Err(_) => {
+                        eprintln!("Frame rate should be a number, not {}", fps);
+                        std::process::exit(1);
+                    }
+                },
+                None => 10.0,
+            };
+            let rule = rule(args.next());
+            let frames = spaces.frames(&rule, Some(FRAME_LIMIT));
+            // Bad frame rates come back as an error from `animate`
+            if let Err(error) = animate(frames, &mut stdout(), fps) {
+                eprintln!("Unable to animate: {}", error);
+                std::process::exit(1);
+            }
+        }
+        _ => solve(&spaces),
     }
-    // Count the occupied seats
-    println!(
-        "Day 11 - Part 1 - Occupied seats: {}",
-        spaces.count_occupied()
-    );
-    // Part 2 - Using line if sight technique
-    spaces = input.parse().unwrap();
-    let mut next_step = spaces.step_part2();
-    while spaces != next_step {
-        spaces = next_step;
-        next_step = spaces.step_part2();
+}
+
+fn solve(spaces: &Spaces) {
+    for (part, rule) in [(1, Rule::part1()), (2, Rule::part2())].iter() {
+        let run = spaces.run_until_stable(rule, None);
+        match run.outcome {
+            // Count the occupied seats
+            Outcome::Stable => println!(
+                "Day 11 - Part {} - Occupied seats: {} (after {} rounds)",
+                part,
+                run.spaces.count_occupied(),
+                run.rounds
+            ),
+            Outcome::Cycle { start, period } => println!(
+                "Day 11 - Part {} - Never settles: repeats every {} rounds from round {}",
+                part, period, start
+            ),
+            Outcome::LimitReached => unreachable!("There's no round limit"),
+        }
     }
-    // Count the occupied seats
-    println!(
-        "Day 11 - Part 2 - Occupied seats: {}",
-        spaces.count_occupied()
-    );
 }
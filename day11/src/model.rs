@@ -1,8 +1,19 @@
 use parse_display::{Display, FromStr};
+mod render;
+mod rule;
+mod run;
+mod simulation;
+mod topology;
 mod vision;
 
+pub use render::{animate, export_frames, Frames};
+pub use rule::{Neighbourhood, Rule};
+pub use run::{Outcome, Run};
+pub use simulation::{Simulation, VisibilityGraph};
+pub use topology::{Layout, Topology};
+
 /// A grid space in the waiting room
-#[derive(Display, FromStr, PartialEq, Eq, Debug, Clone, Copy)]
+#[derive(Display, FromStr, PartialEq, Eq, Hash, Debug, Clone, Copy)]
 pub enum Space {
     #[display("#")]
     OccupiedSeat,
@@ -15,16 +26,12 @@ pub enum Space {
 impl Space {
     /// Returns true if this seat is occupied
     fn is_occupied(&self) -> bool {
-        if let Space::OccupiedSeat = self {
-            true
-        } else {
-            false
-        }
+        matches!(self, Space::OccupiedSeat)
     }
 }
 
 /// All the spaces in the waiting room
-#[derive(Default, Debug, PartialEq, Eq)]
+#[derive(Default, Debug, PartialEq, Eq, Hash, Clone)]
 pub struct Spaces {
     // Data is stored [y,y,y]
     // So to access coordinate (x,y), index is y * width + x
@@ -34,46 +41,33 @@ pub struct Spaces {
     data: Vec<Space>,
     width: usize,
     height: usize,
+    topology: Topology,
 }
 
 impl Spaces {
-    /// Runs through one step of iteration
-
+    /// Runs through one step of iteration using the part 1 rules
+    ///
     /// Returns the new state
     pub fn step(&self) -> Self {
+        self.step_with(&Rule::part1())
+    }
+
+    /// Runs through one step of iteration using any seating rule
+    ///
+    /// Returns the new state
+    pub fn step_with(&self, rule: &Rule) -> Self {
         let converter = |x, y, space| {
-            match space {
-                Space::EmptySeat => {
-                    // If a seat is empty (L) and there are no occupied seats
-                    // adjacent to it, the seat becomes occupied.
-                    if self.adjacent(x, y).iter().all(|space| !space.is_occupied()) {
-                        // Sit in the chair
-                        Space::OccupiedSeat
-                    } else {
-                        space
-                    }
-                }
-                Space::OccupiedSeat => {
-                    // If a seat is occupied (#) and four or more seats
-                    // adjacent to it are also occupied, the seat becomes
-                    // empty.
-                    if self
-                        .adjacent(x, y)
-                        .iter()
-                        .filter(|space| space.is_occupied())
-                        .take(4)
-                        .count()
-                        == 4
-                    {
-                        // Get out of the chair
-                        Space::EmptySeat
-                    } else {
-                        space
-                    }
-                }
+            let occupied = match space {
                 // We don't care about floor - nothing changes there
-                Space::Floor => space,
-            }
+                Space::Floor => 0,
+                _ => self.count_occupied_neighbours(
+                    x,
+                    y,
+                    rule.neighbourhood,
+                    rule.count_limit(space),
+                ),
+            };
+            rule.apply(space, occupied)
         };
         self.step_generic(converter)
     }
@@ -94,7 +88,7 @@ impl Spaces {
                     x,
                     y,
                     self.get(x, y)
-                        .expect(&format!("Unabel to get cell at x: {} y: {}", x, y)),
+                        .unwrap_or_else(|| panic!("Unable to get cell at x: {} y: {}", x, y)),
                 )
             })
             .map(|(x, y, space)| converter_func(x, y, *space))
@@ -103,36 +97,21 @@ impl Spaces {
             data,
             height: self.height,
             width: self.width,
+            topology: self.topology,
         }
     }
 
     /// Returns a vec of adjacent spaces to a given space
     /// Normal spaces have 8 adjacents (horizontal, vertical, and the two diagnals)
-    /// Edge spaces, only get 3
-    /// Corner spaces, only get 2
+    /// Edge spaces, only get 5
+    /// Corner spaces, only get 3
+    /// Other topologies have different counts: 4 for von Neumann, 6 for hexagonal,
+    /// and a room that wraps has no edges or corners
     pub fn adjacent(&self, x: usize, y: usize) -> Vec<&Space> {
-        // All possible directions we can go
-        let deltas = [-1, 0, 1];
-        // Go every direction
-        deltas
+        self.topology
+            .directions()
             .iter()
-            .flat_map(|dx| deltas.iter().map(move |dy| (dx, dy)))
-            // Don't include the current space
-            .filter(|(&dx, &dy)| !(dx == 0 && dy == 0))
-            // Do the math as i64 (rather than usize)
-            // Don't go left if we're already at the left
-            .filter(|(&dx, _dy)| !(x == 0 && dx == -1))
-            // Don't go right if we're at the end
-            .filter(|(&dx, _dy)| !(x == self.width - 1 && dx == 1))
-            // Don't go up if we're at the top
-            .filter(|(_dx, &dy)| !(y == 0 && dy == -1))
-            // Don't go up if we're at the top
-            .filter(|(_dx, &dy)| !(y == self.height - 1 && dy == 1))
-            // Convert the deltas into coordinates
-            .map(|(dx, dy)| (x as i64 + dx, y as i64 + dy))
-            // i64 to usize
-            .map(|(x, y)| (x as usize, y as usize))
-            // coordinates to actual Spaces
+            .filter_map(|&direction| self.offset(x, y, direction))
             .flat_map(|(x, y)| self.get(x, y))
             .collect()
     }
@@ -145,11 +124,19 @@ impl Spaces {
 
 impl std::fmt::Display for Spaces {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let hexagonal = self.topology.layout == Layout::Hexagonal;
         for y in 0..self.height {
+            if hexagonal {
+                // Each row of hexagons sits half a space further right
+                write!(f, "{:1$}", "", y)?;
+            }
             for x in 0..self.width {
                 let space = self
                     .get(x, y)
-                    .expect(&format!("Unable to get a space for x: {} y: {}", x, y));
+                    .unwrap_or_else(|| panic!("Unable to get a space for x: {} y: {}", x, y));
+                if hexagonal && x != 0 {
+                    write!(f, " ")?;
+                }
                 write!(f, "{}", space)?;
             }
             if y != self.height - 1 {
@@ -185,6 +172,33 @@ impl Spaces {
     pub fn height(&self) -> usize {
         self.height
     }
+
+    /// The shape of the room
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Returns the same spaces, arranged in a different shape of room
+    pub fn with_topology(self, topology: Topology) -> Self {
+        Spaces { topology, ..self }
+    }
+}
+
+/// The example waiting room from the puzzle, for the tests
+#[cfg(test)]
+pub(crate) fn example() -> Spaces {
+    r#"L.LL.LL.LL
+LLLLLLL.LL
+L.L.L..L..
+LLLL.LL.LL
+L.LL.LL.LL
+L.LLLLL.LL
+..L.L.....
+LLLLLLLLLL
+L.LLLLLL.L
+L.LLLLL.LL"#
+        .parse()
+        .unwrap()
 }
 
 #[cfg(test)]
@@ -0,0 +1,220 @@
+//! Pictures of the waiting room, so we can watch it settle down
+//!
+//! Frames can be saved as PPM images (one file per round, easy to stitch into a gif with
+//! ImageMagick or ffmpeg), or played straight in the terminal with ANSI colours.
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    thread::sleep,
+    time::Duration,
+};
+
+use super::{Rule, Simulation, Space, Spaces};
+
+impl Space {
+    /// The colour to paint this space in images (red, green, blue)
+    pub fn colour(&self) -> [u8; 3] {
+        match self {
+            Space::OccupiedSeat => [220, 60, 40],
+            Space::EmptySeat => [60, 170, 80],
+            Space::Floor => [40, 40, 40],
+        }
+    }
+
+    /// The ANSI escape code that sets the terminal foreground colour for this space
+    fn ansi_colour(&self) -> &'static str {
+        match self {
+            Space::OccupiedSeat => "\x1b[31m",
+            Space::EmptySeat => "\x1b[32m",
+            Space::Floor => "\x1b[90m",
+        }
+    }
+}
+
+/// Every state of the room, starting with the one we were given, one per round until it
+/// settles down (or we hit the round limit)
+pub struct Frames {
+    simulation: Simulation,
+    /// Rounds left before we give up. None means keep going forever
+    rounds_left: Option<usize>,
+    started: bool,
+    finished: bool,
+}
+
+impl Iterator for Frames {
+    type Item = Spaces;
+
+    fn next(&mut self) -> Option<Spaces> {
+        if !self.started {
+            self.started = true;
+            return Some(self.simulation.spaces());
+        }
+        if self.finished || self.rounds_left == Some(0) {
+            return None;
+        }
+        if let Some(rounds_left) = self.rounds_left.as_mut() {
+            *rounds_left -= 1;
+        }
+        if self.simulation.step() {
+            Some(self.simulation.spaces())
+        } else {
+            // Nothing changed, so there's nothing new to draw
+            self.finished = true;
+            None
+        }
+    }
+}
+
+impl Spaces {
+    /// Returns this room, then the room after each round, until it stops changing or
+    /// `limit` rounds have been run. Rooms that cycle forever need a limit if you want the
+    /// iterator to end
+    pub fn frames(&self, rule: &Rule, limit: Option<usize>) -> Frames {
+        Frames {
+            simulation: Simulation::new(self, *rule),
+            rounds_left: limit,
+            started: false,
+            finished: false,
+        }
+    }
+
+    /// Writes the room as a binary PPM image, with each space drawn as a `scale` x `scale` square
+    pub fn write_ppm<W: Write>(&self, scale: usize, out: &mut W) -> io::Result<()> {
+        write!(
+            out,
+            "P6\n{} {}\n255\n",
+            self.width * scale,
+            self.height * scale
+        )?;
+        let mut row = Vec::with_capacity(self.width * scale * 3);
+        for y in 0..self.height {
+            row.clear();
+            for space in &self.data[y * self.width..(y + 1) * self.width] {
+                for _ in 0..scale {
+                    row.extend_from_slice(&space.colour());
+                }
+            }
+            for _ in 0..scale {
+                out.write_all(&row)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The room as coloured text, ready to print in a terminal
+    pub fn to_ansi(&self) -> String {
+        let mut out = String::with_capacity(self.data.len() * 6);
+        for y in 0..self.height {
+            for space in &self.data[y * self.width..(y + 1) * self.width] {
+                out.push_str(space.ansi_colour());
+                out.push_str(&space.to_string());
+            }
+            // Reset the colour at the end of each line
+            out.push_str("\x1b[0m\n");
+        }
+        out
+    }
+}
+
+/// Saves every frame as `frame_0000.ppm`, `frame_0001.ppm`, .. in `dir`
+///
+/// Returns the number of frames written
+pub fn export_frames<I>(frames: I, dir: &Path, scale: usize) -> io::Result<usize>
+where
+    I: IntoIterator<Item = Spaces>,
+{
+    std::fs::create_dir_all(dir)?;
+    let mut count = 0;
+    for (round, spaces) in frames.into_iter().enumerate() {
+        let path = dir.join(format!("frame_{:04}.ppm", round));
+        let mut out = BufWriter::new(File::create(path)?);
+        spaces.write_ppm(scale, &mut out)?;
+        out.flush()?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Plays the frames in the terminal, redrawing the room in place `fps` times a second.
+/// Fails with `InvalidInput` unless `fps` is a finite number above 0
+pub fn animate<I, W>(frames: I, out: &mut W, fps: f64) -> io::Result<()>
+where
+    I: IntoIterator<Item = Spaces>,
+    W: Write,
+{
+    if !fps.is_finite() || fps <= 0.0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Frame rate has to be above 0, not {}", fps),
+        ));
+    }
+    let delay = Duration::from_secs_f64(1.0 / fps);
+    // Clear the screen and hide the cursor
+    write!(out, "\x1b[2J\x1b[?25l")?;
+    for (round, spaces) in frames.into_iter().enumerate() {
+        // Jump back to the top left and draw over the last frame
+        write!(
+            out,
+            "\x1b[HRound {} - Occupied seats: {}\n{}",
+            round,
+            spaces.count_occupied(),
+            spaces.to_ansi()
+        )?;
+        out.flush()?;
+        sleep(delay);
+    }
+    // Show the cursor again
+    write!(out, "\x1b[?25h")?;
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::example;
+    use crate::{Rule, Spaces};
+
+    #[test]
+    fn test_frames() {
+        let frames: Vec<Spaces> = example().frames(&Rule::part1(), None).collect();
+        // The starting room, then the 5 rounds that change something
+        assert_eq!(frames.len(), 6);
+        assert_eq!(frames[0], example());
+        assert_eq!(frames[1], example().step());
+        assert_eq!(frames[5].count_occupied(), 37);
+        assert_eq!(example().frames(&Rule::part1(), Some(2)).count(), 3);
+    }
+
+    #[test]
+    fn test_ppm() {
+        let spaces: Spaces = "#L\n..".parse().unwrap();
+        let mut out = Vec::new();
+        spaces.write_ppm(2, &mut out).unwrap();
+        let header = b"P6\n4 4\n255\n";
+        assert_eq!(&out[..header.len()], header);
+        let pixels = &out[header.len()..];
+        assert_eq!(pixels.len(), 4 * 4 * 3);
+        // Top left is occupied, top right is empty, both rows of each square match
+        assert_eq!(&pixels[0..3], &[220, 60, 40]);
+        assert_eq!(&pixels[6..9], &[60, 170, 80]);
+        assert_eq!(&pixels[12..24], &pixels[0..12]);
+        assert_eq!(&pixels[24..27], &[40, 40, 40]);
+    }
+
+    #[test]
+    fn test_ansi() {
+        let spaces: Spaces = "#L.".parse().unwrap();
+        assert_eq!(spaces.to_ansi(), "\x1b[31m#\x1b[32mL\x1b[90m.\x1b[0m\n");
+    }
+
+    #[test]
+    fn test_bad_frame_rate() {
+        for &fps in &[0.0, -5.0, f64::NAN, f64::INFINITY] {
+            let mut out = Vec::new();
+            let got = super::animate(vec![example()], &mut out, fps);
+            assert_eq!(got.unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+            assert!(out.is_empty());
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Seating policies: who people look at, and how crowded is too crowded
+
+use crate::Space;
+
+/// Which seats a person pays attention to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighbourhood {
+    /// The spaces touching the seat
+    Adjacent,
+    /// The first seat in each direction, however far away it is
+    LineOfSight,
+    /// The first seat in each direction, if it's at most `radius` spaces away
+    LimitedSight(usize),
+}
+
+impl Neighbourhood {
+    /// How far to look in each direction. None means forever
+    pub fn range(&self) -> Option<usize> {
+        match *self {
+            // Looking 1 space away is the same as only checking adjacent spaces
+            Neighbourhood::Adjacent => Some(1),
+            Neighbourhood::LineOfSight => None,
+            Neighbourhood::LimitedSight(radius) => Some(radius),
+        }
+    }
+}
+
+/// A seating policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rule {
+    /// Who each person looks at
+    pub neighbourhood: Neighbourhood,
+    /// An empty seat gets taken if at most this many neighbours are occupied
+    pub sit_threshold: usize,
+    /// An occupied seat gets left if at least this many neighbours are occupied
+    pub leave_threshold: usize,
+}
+
+impl Rule {
+    /// Part 1: sit if no adjacent seats are taken, leave if 4 or more are
+    pub fn part1() -> Rule {
+        Rule {
+            neighbourhood: Neighbourhood::Adjacent,
+            sit_threshold: 0,
+            leave_threshold: 4,
+        }
+    }
+
+    /// Part 2: sit if you can't see anyone, leave if you can see 5 or more people
+    pub fn part2() -> Rule {
+        Rule {
+            neighbourhood: Neighbourhood::LineOfSight,
+            sit_threshold: 0,
+            leave_threshold: 5,
+        }
+    }
+
+    /// The most occupied neighbours we need to count before we know what happens to `space`
+    pub fn count_limit(&self, space: Space) -> usize {
+        match space {
+            Space::EmptySeat => self.sit_threshold + 1,
+            Space::OccupiedSeat => self.leave_threshold,
+            Space::Floor => 0,
+        }
+    }
+
+    /// What `space` becomes, given how many of its neighbours are occupied
+    /// (counted up to `count_limit`)
+    pub fn apply(&self, space: Space, occupied_neighbours: usize) -> Space {
+        match space {
+            // Sit in the chair
+            Space::EmptySeat if occupied_neighbours <= self.sit_threshold => Space::OccupiedSeat,
+            // Get out of the chair
+            Space::OccupiedSeat if occupied_neighbours >= self.leave_threshold => Space::EmptySeat,
+            // We don't care about floor - nothing changes there
+            _ => space,
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! Runs the simulation until it settles down (or doesn't)
+
+use super::{Rule, Simulation, Spaces};
+
+/// Why the simulation stopped
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Outcome {
+    /// Another round wouldn't change anything
+    Stable,
+    /// The room never settles: the state after round `start + period` is the same as after
+    /// round `start`, so it'll repeat forever
+    Cycle { start: usize, period: usize },
+    /// We ran out of rounds before it settled or repeated
+    LimitReached,
+}
+
+/// The result of running the simulation
+#[derive(Debug, PartialEq, Eq)]
+pub struct Run {
+    /// The state when we stopped
+    pub spaces: Spaces,
+    /// The number of rounds that were run to get to `spaces`
+    pub rounds: usize,
+    /// Why we stopped
+    pub outcome: Outcome,
+}
+
+impl Spaces {
+    /// Keeps running rounds until the seating stops changing, a previous seating comes back
+    /// around, or `limit` rounds have been run
+    pub fn run_until_stable(&self, rule: &Rule, limit: Option<usize>) -> Run {
+        let mut simulation = Simulation::new(self, *rule);
+        let (rounds, outcome) = simulation.run(limit);
+        Run {
+            spaces: simulation.spaces(),
+            rounds,
+            outcome,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Outcome;
+    use crate::model::example;
+    use crate::{Neighbourhood, Rule, Spaces};
+
+    #[test]
+    fn test_stable() {
+        let run = example().run_until_stable(&Rule::part1(), None);
+        assert_eq!(run.outcome, Outcome::Stable);
+        assert_eq!(run.rounds, 5);
+        assert_eq!(run.spaces.count_occupied(), 37);
+        let run = example().run_until_stable(&Rule::part2(), None);
+        assert_eq!(run.outcome, Outcome::Stable);
+        assert_eq!(run.rounds, 6);
+        assert_eq!(run.spaces.count_occupied(), 26);
+    }
+
+    #[test]
+    fn test_limit() {
+        let run = example().run_until_stable(&Rule::part1(), Some(2));
+        assert_eq!(run.outcome, Outcome::LimitReached);
+        assert_eq!(run.rounds, 2);
+        assert_eq!(run.spaces, example().step().step());
+    }
+
+    #[test]
+    fn test_cycle() {
+        // Everyone sits down, then everyone gets up, forever
+        let rule = Rule {
+            neighbourhood: Neighbourhood::Adjacent,
+            sit_threshold: 8,
+            leave_threshold: 0,
+        };
+        let run = example().run_until_stable(&rule, Some(100));
+        assert_eq!(
+            run.outcome,
+            Outcome::Cycle {
+                start: 0,
+                period: 2
+            }
+        );
+        assert_eq!(run.rounds, 2);
+        assert_eq!(run.spaces, example());
+        // Start from the everyone sitting state, and the cycle starts straight away too
+        let run = example().step_with(&rule).run_until_stable(&rule, None);
+        assert_eq!(
+            run.outcome,
+            Outcome::Cycle {
+                start: 0,
+                period: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_cycle_after_a_while() {
+        // L## -> #LL -> ### -> LLL -> ### ...
+        let rule = Rule {
+            neighbourhood: Neighbourhood::Adjacent,
+            sit_threshold: 1,
+            leave_threshold: 1,
+        };
+        let spaces: Spaces = "L##".parse().unwrap();
+        let run = spaces.run_until_stable(&rule, None);
+        assert_eq!(
+            run.outcome,
+            Outcome::Cycle {
+                start: 2,
+                period: 2
+            }
+        );
+        assert_eq!(run.rounds, 4);
+        assert_eq!(run.spaces.to_string(), "###");
+    }
+}
@@ -0,0 +1,255 @@
+//! A fast way to run lots of rounds
+//!
+//! Working out who can see who means walking up to 8 rays per seat, but the answer never changes
+//! because floor never changes. So we do it once, store the result as a compact adjacency list,
+//! and then each round is just a walk over the seats, flipping between two buffers.
+
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+};
+
+use super::{Neighbourhood, Outcome, Rule, Space, Spaces};
+
+/// For every seat, the seats in its neighbourhood
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VisibilityGraph {
+    /// Where each seat is in the grid (index = y * width + x)
+    seats: Vec<usize>,
+    /// Seat `i` can see the seats in `neighbours[offsets[i]..offsets[i + 1]]`
+    offsets: Vec<usize>,
+    /// Indexes into `seats`
+    neighbours: Vec<u32>,
+}
+
+impl VisibilityGraph {
+    pub fn new(spaces: &Spaces, neighbourhood: Neighbourhood) -> VisibilityGraph {
+        let seats: Vec<usize> = (0..spaces.data.len())
+            .filter(|&index| spaces.data[index] != Space::Floor)
+            .collect();
+        // Grid index -> seat number
+        let mut seat_numbers = vec![u32::MAX; spaces.data.len()];
+        for (number, &index) in seats.iter().enumerate() {
+            seat_numbers[index] = number as u32;
+        }
+        let mut offsets = Vec::with_capacity(seats.len() + 1);
+        let mut neighbours = Vec::with_capacity(seats.len() * 8);
+        offsets.push(0);
+        for &index in &seats {
+            let (x, y) = (index % spaces.width, index / spaces.width);
+            neighbours.extend(
+                spaces
+                    .neighbours(x, y, neighbourhood)
+                    .map(|(x, y)| seat_numbers[y * spaces.width + x]),
+            );
+            offsets.push(neighbours.len());
+        }
+        VisibilityGraph {
+            seats,
+            offsets,
+            neighbours,
+        }
+    }
+
+    /// The number of seats
+    pub fn len(&self) -> usize {
+        self.seats.len()
+    }
+
+    /// True if there are no seats at all
+    pub fn is_empty(&self) -> bool {
+        self.seats.is_empty()
+    }
+
+    /// The seat numbers in the neighbourhood of seat number `seat`
+    pub fn neighbours(&self, seat: usize) -> &[u32] {
+        &self.neighbours[self.offsets[seat]..self.offsets[seat + 1]]
+    }
+
+    /// Works out the round after `current` into `next`. Returns true if any seat changes
+    fn next_round(&self, rule: &Rule, current: &[bool], next: &mut [bool]) -> bool {
+        let mut changed = false;
+        for seat in 0..self.len() {
+            let space = if current[seat] {
+                Space::OccupiedSeat
+            } else {
+                Space::EmptySeat
+            };
+            let occupied = self
+                .neighbours(seat)
+                .iter()
+                .filter(|&&neighbour| current[neighbour as usize])
+                .take(rule.count_limit(space))
+                .count();
+            next[seat] = rule.apply(space, occupied).is_occupied();
+            changed |= next[seat] != current[seat];
+        }
+        changed
+    }
+}
+
+/// Runs rounds in place over a precomputed visibility graph
+#[derive(Debug, Clone)]
+pub struct Simulation {
+    rule: Rule,
+    graph: VisibilityGraph,
+    /// The room we started with. Used for the floor and size when turning back into `Spaces`
+    layout: Spaces,
+    /// Whether each seat is occupied this round
+    current: Vec<bool>,
+    /// Where the next round gets written
+    next: Vec<bool>,
+}
+
+impl Simulation {
+    pub fn new(spaces: &Spaces, rule: Rule) -> Simulation {
+        let graph = VisibilityGraph::new(spaces, rule.neighbourhood);
+        let current: Vec<bool> = graph
+            .seats
+            .iter()
+            .map(|&index| spaces.data[index].is_occupied())
+            .collect();
+        Simulation {
+            rule,
+            next: current.clone(),
+            current,
+            graph,
+            layout: spaces.clone(),
+        }
+    }
+
+    /// Works out the next round into the spare buffer. Returns true if any seat changes
+    fn compute_next(&mut self) -> bool {
+        self.graph
+            .next_round(&self.rule, &self.current, &mut self.next)
+    }
+
+    /// The seats after `rounds` rounds, starting from `seats`
+    fn replay(&self, seats: &[bool], rounds: usize) -> Vec<bool> {
+        let mut current = seats.to_vec();
+        let mut next = current.clone();
+        for _ in 0..rounds {
+            self.graph.next_round(&self.rule, &current, &mut next);
+            std::mem::swap(&mut current, &mut next);
+        }
+        current
+    }
+
+    /// Runs one round. Returns true if any seat changed
+    pub fn step(&mut self) -> bool {
+        let changed = self.compute_next();
+        std::mem::swap(&mut self.current, &mut self.next);
+        changed
+    }
+
+    /// Keeps running rounds until the seating stops changing, a previous seating comes back
+    /// around, or `limit` rounds have been run. Returns the number of rounds run and why we stopped
+    pub fn run(&mut self, limit: Option<usize>) -> (usize, Outcome) {
+        // Storing every state would take a copy of the room per round, so we only keep a hash of
+        // each one, and the round it first appeared after. When a hash comes back around, we
+        // replay from the start to make sure it's really the same state
+        let start_seats = self.current.clone();
+        let mut seen = HashMap::new();
+        seen.insert(hash_seats(&self.current), 0);
+        let mut rounds = 0;
+        loop {
+            if !self.compute_next() {
+                return (rounds, Outcome::Stable);
+            }
+            if limit.map(|limit| rounds >= limit).unwrap_or(false) {
+                return (rounds, Outcome::LimitReached);
+            }
+            std::mem::swap(&mut self.current, &mut self.next);
+            rounds += 1;
+            let hash = hash_seats(&self.current);
+            match seen.get(&hash) {
+                Some(&start) if self.replay(&start_seats, start) == self.current => {
+                    let period = rounds - start;
+                    return (rounds, Outcome::Cycle { start, period });
+                }
+                _ => {
+                    seen.entry(hash).or_insert(rounds);
+                }
+            }
+        }
+    }
+
+    /// Returns the number of occupied seats
+    pub fn count_occupied(&self) -> usize {
+        self.current.iter().filter(|&&occupied| occupied).count()
+    }
+
+    /// The current state of the room
+    pub fn spaces(&self) -> Spaces {
+        let mut out = self.layout.clone();
+        for (&index, &occupied) in self.graph.seats.iter().zip(&self.current) {
+            out.data[index] = if occupied {
+                Space::OccupiedSeat
+            } else {
+                Space::EmptySeat
+            };
+        }
+        out
+    }
+}
+
+/// A 64 bit hash of the seats, so we can remember a round without keeping a copy of it
+fn hash_seats(seats: &[bool]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    seats.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Simulation, VisibilityGraph};
+    use crate::model::example;
+    use crate::{Neighbourhood, Rule, Spaces, Topology};
+
+    #[test]
+    fn test_graph() {
+        let spaces: Spaces = "L.L\n...\n#.L".parse().unwrap();
+        let graph = VisibilityGraph::new(&spaces, Neighbourhood::LineOfSight);
+        assert_eq!(graph.len(), 4);
+        // Top left sees top right, bottom left and bottom right
+        assert_eq!(graph.neighbours(0), &[1, 2, 3]);
+        let graph = VisibilityGraph::new(&spaces, Neighbourhood::Adjacent);
+        assert!((0..4).all(|seat| graph.neighbours(seat).is_empty()));
+    }
+
+    #[test]
+    fn test_matches_step_with() {
+        let topologies = [
+            Topology::rectangle(),
+            Topology::torus(),
+            Topology::von_neumann(),
+            Topology::hexagonal(),
+            Topology::hexagonal().wrapping(),
+        ];
+        for (rule, &topology) in [Rule::part1(), Rule::part2()]
+            .iter()
+            .flat_map(|rule| topologies.iter().map(move |topology| (rule, topology)))
+        {
+            let mut spaces = example().with_topology(topology);
+            let mut simulation = Simulation::new(&spaces, *rule);
+            // Rooms that wrap around might never settle, so only check the first few rounds
+            for _ in 0..20 {
+                let changed = simulation.step();
+                let next = spaces.step_with(rule);
+                assert_eq!(changed, next != spaces);
+                assert_eq!(simulation.spaces(), next);
+                if !changed {
+                    break;
+                }
+                spaces = next;
+            }
+        }
+    }
+
+    #[test]
+    fn test_count() {
+        let mut simulation = Simulation::new(&example(), Rule::part2());
+        simulation.run(None);
+        assert_eq!(simulation.count_occupied(), 26);
+    }
+}
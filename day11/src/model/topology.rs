@@ -0,0 +1,102 @@
+//! The shape of the waiting room: which spaces are next to each other, and what happens at the walls
+
+/// Which spaces touch each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Layout {
+    /// Square spaces, touching in 8 directions (including diagonals)
+    Moore,
+    /// Square spaces, touching in only 4 directions (up, down, left, right)
+    VonNeumann,
+    /// Hexagonal spaces, touching in 6 directions.
+    ///
+    /// Stored in axial coordinates: each row sits half a space further right than the one above,
+    /// so the room is a parallelogram, eg.
+    ///
+    /// ```text
+    /// L . L
+    ///  L L .
+    ///   . L L
+    /// ```
+    Hexagonal,
+}
+
+/// The shape of the waiting room
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Topology {
+    pub layout: Layout,
+    /// If true, walking off one edge of the room brings you back in on the opposite edge
+    pub wraps: bool,
+}
+
+impl Default for Topology {
+    /// The puzzle's room: a rectangle with walls, where people can look diagonally
+    fn default() -> Topology {
+        Topology::rectangle()
+    }
+}
+
+/// The 8 directions on a square grid, as (dx, dy). The first 4 don't go diagonally
+const SQUARE_DIRECTIONS: [(i64, i64); 8] = [
+    (-1, 0),
+    (0, -1),
+    (1, 0),
+    (0, 1),
+    (-1, -1),
+    (1, -1),
+    (1, 1),
+    (-1, 1),
+];
+
+/// The 6 directions on a hex grid in axial coordinates, as (dx, dy)
+const HEX_DIRECTIONS: [(i64, i64); 6] = [(-1, 0), (0, -1), (1, -1), (1, 0), (0, 1), (-1, 1)];
+
+impl Topology {
+    /// A rectangle with walls, and 8 directions. What the puzzle uses
+    pub fn rectangle() -> Topology {
+        Topology {
+            layout: Layout::Moore,
+            wraps: false,
+        }
+    }
+
+    /// A rectangle where each edge wraps around to the opposite one, with 8 directions
+    pub fn torus() -> Topology {
+        Topology {
+            layout: Layout::Moore,
+            wraps: true,
+        }
+    }
+
+    /// A rectangle with walls, where people only look up, down, left and right
+    pub fn von_neumann() -> Topology {
+        Topology {
+            layout: Layout::VonNeumann,
+            wraps: false,
+        }
+    }
+
+    /// A parallelogram of hexagons with walls
+    pub fn hexagonal() -> Topology {
+        Topology {
+            layout: Layout::Hexagonal,
+            wraps: false,
+        }
+    }
+
+    /// The same layout, but wrapping around at the edges
+    pub fn wrapping(self) -> Topology {
+        Topology {
+            wraps: true,
+            ..self
+        }
+    }
+
+    /// The directions someone can look in, as (dx, dy)
+    pub fn directions(&self) -> &'static [(i64, i64)] {
+        match self.layout {
+            Layout::Moore => &SQUARE_DIRECTIONS,
+            Layout::VonNeumann => &SQUARE_DIRECTIONS[..4],
+            Layout::Hexagonal => &HEX_DIRECTIONS,
+        }
+    }
+}
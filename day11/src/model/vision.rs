@@ -3,153 +3,108 @@
 
 use crate::Space;
 
-use super::Spaces;
+use super::{Neighbourhood, Rule, Spaces};
 
 impl Spaces {
+    /// Returns the coordinates one step away from x, y in a direction, if that's still in the room.
+    /// In a room that wraps, it always is
+    pub(super) fn offset(
+        &self,
+        x: usize,
+        y: usize,
+        (dx, dy): (i64, i64),
+    ) -> Option<(usize, usize)> {
+        let x = x as i64 + dx;
+        let y = y as i64 + dy;
+        let (width, height) = (self.width as i64, self.height as i64);
+        if self.topology.wraps {
+            Some((x.rem_euclid(width) as usize, y.rem_euclid(height) as usize))
+        } else if x < 0 || y < 0 || x >= width || y >= height {
+            None
+        } else {
+            Some((x as usize, y as usize))
+        }
+    }
+
+    /// Looks from x, y in a direction and returns the coordinates of the first seat seen, if any.
+    /// Gives up after `range` spaces (None means look all the way to the wall).
+    /// In a room that wraps, a ray that comes back around to x, y without seeing anyone gives up
+    fn first_seat(
+        &self,
+        x: usize,
+        y: usize,
+        direction: (i64, i64),
+        range: Option<usize>,
+    ) -> Option<(usize, usize)> {
+        let start = (x, y);
+        let (mut x, mut y) = start;
+        let mut distance = 0;
+        while range.map(|range| distance < range).unwrap_or(true) {
+            let (next_x, next_y) = self.offset(x, y, direction)?;
+            if (next_x, next_y) == start {
+                // We've looked all the way around the room
+                return None;
+            }
+            x = next_x;
+            y = next_y;
+            distance += 1;
+            match self.get(x, y) {
+                // Floor doesn't block the view, so keep searching
+                Some(Space::Floor) => (),
+                Some(_) => return Some((x, y)),
+                None => return None,
+            }
+        }
+        None
+    }
+
+    /// The coordinates of every seat in the neighbourhood of x, y
+    pub fn neighbours(
+        &self,
+        x: usize,
+        y: usize,
+        neighbourhood: Neighbourhood,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let range = neighbourhood.range();
+        self.topology
+            .directions()
+            .iter()
+            .filter_map(move |&direction| self.first_seat(x, y, direction, range))
+    }
+
+    /// Returns the number of occupied seats in the neighbourhood of grid space x, y
+    /// Once the count hits `max` the function will return early
+    pub fn count_occupied_neighbours(
+        &self,
+        x: usize,
+        y: usize,
+        neighbourhood: Neighbourhood,
+        max: usize,
+    ) -> usize {
+        self.neighbours(x, y, neighbourhood)
+            .filter(|&(x, y)| self.get(x, y) == Some(&Space::OccupiedSeat))
+            .take(max)
+            .count()
+    }
+
     /// Starting at grid space x (col number) and y (row number),
     /// It'll return the number of occupied seats visible (along the 8 direction lines)
     /// An empty seat blocks the view of occupied seats
     /// Once the count hits `max` the function will return early. `max` can be
     /// from 1-8 because we search in 8 directions
     pub fn count_visible_occupied_seats(&self, x: usize, y: usize, max: usize) -> usize {
-        let mut count = 0;
-        // Closure used to see if a space matches our search
-        let seat_search = |x, y| -> Option<bool> {
-            match self.get(x, y) {
-                // An occupied seat adds to the count
-                Some(Space::OccupiedSeat) => Some(true),
-                // An empty seat blocks the rest of the view, so stop searching
-                Some(Space::EmptySeat) => Some(false),
-                // Anything else means, keep searching
-                _ => None,
-            }
-        };
-        // Look left
-        if (0..x)
-            .rev()
-            .find_map(|x| seat_search(x, y))
-            .unwrap_or(false)
-        {
-            count += 1;
-            if count >= max {
-                return count;
-            }
-        }
-        // Look up
-        if (0..y)
-            .rev()
-            .find_map(|y| seat_search(x, y))
-            .unwrap_or(false)
-        {
-            count += 1;
-            if count >= max {
-                return count;
-            }
-        }
-        // Look right
-        if (x + 1..self.width)
-            .find_map(|x| seat_search(x, y))
-            .unwrap_or(false)
-        {
-            count += 1;
-            if count >= max {
-                return count;
-            }
-        }
-        // Look down
-        if (y + 1..self.height)
-            .find_map(|y| seat_search(x, y))
-            .unwrap_or(false)
-        {
-            count += 1;
-            if count >= max {
-                return count;
-            }
-        }
-        // Look left and up diagnally
-        if (0..x)
-            .rev()
-            .zip((0..y).rev())
-            .find_map(|(x, y)| seat_search(x, y))
-            .unwrap_or(false)
-        {
-            count += 1;
-            if count >= max {
-                return count;
-            }
-        }
-        // Look right and up diagnally
-        if (x + 1..self.width)
-            .zip((0..y).rev())
-            .find_map(|(x, y)| seat_search(x, y))
-            .unwrap_or(false)
-        {
-            count += 1;
-            if count >= max {
-                return count;
-            }
-        }
-        // Look right and down diagnally
-        if (x + 1..self.width)
-            .zip(y + 1..self.height)
-            .find_map(|(x, y)| seat_search(x, y))
-            .unwrap_or(false)
-        {
-            count += 1;
-            if count >= max {
-                return count;
-            }
-        }
-        // Look left and down diagnally
-        if ((0..x).rev())
-            .zip(y + 1..self.height)
-            .find_map(|(x, y)| seat_search(x, y))
-            .unwrap_or(false)
-        {
-            count += 1;
-            if count >= max {
-                return count;
-            }
-        }
-        count
+        self.count_occupied_neighbours(x, y, Neighbourhood::LineOfSight, max)
     }
 
-    /// Takes a step forward in the animation, returning the new state
+    /// Takes a step forward in the animation using the part 2 rules, returning the new state
     pub fn step_part2(&self) -> Self {
-        let converter = |x, y, space| {
-            match space {
-                Space::EmptySeat => {
-                    // If a seat is empty (L) and there are no occupied seats
-                    // in line of sight, the seat becomes occupied.
-                    if self.count_visible_occupied_seats(x, y, 1) == 0 {
-                        // Sit in the chair
-                        Space::OccupiedSeat
-                    } else {
-                        space
-                    }
-                }
-                Space::OccupiedSeat => {
-                    // If a seat is occupied (#) and five or more seats
-                    // adjacent to it are also occupied, the seat becomes
-                    // empty.
-                    if self.count_visible_occupied_seats(x, y, 5) == 5 {
-                        // Get out of the chair
-                        Space::EmptySeat
-                    } else {
-                        space
-                    }
-                }
-                // We don't care about floor - nothing changes there
-                Space::Floor => space,
-            }
-        };
-        self.step_generic(converter)
+        self.step_with(&Rule::part2())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::Spaces;
+    use crate::{Neighbourhood, Rule, Spaces, Topology};
 
     #[test]
     fn test_count_seat_searchs_8() {
@@ -194,9 +149,115 @@ mod tests {
         assert_eq!(0, spaces.count_visible_occupied_seats(3, 3, 8));
     }
 
+    #[test]
+    fn test_limited_sight() {
+        let input = "#..#...
+.......
+...L..#
+.......
+...#...";
+        let spaces: Spaces = input.parse().unwrap();
+        let count = |neighbourhood| spaces.count_occupied_neighbours(3, 2, neighbourhood, 8);
+        assert_eq!(0, count(Neighbourhood::Adjacent));
+        assert_eq!(2, count(Neighbourhood::LimitedSight(2)));
+        assert_eq!(3, count(Neighbourhood::LimitedSight(3)));
+        // The seat in the top left corner isn't on a diagonal
+        assert_eq!(3, count(Neighbourhood::LineOfSight));
+    }
+
+    #[test]
+    fn test_custom_rule() {
+        // Sit if at most one neighbour is sitting, leave if 2 or more are, within 2 spaces
+        let rule = Rule {
+            neighbourhood: Neighbourhood::LimitedSight(2),
+            sit_threshold: 1,
+            leave_threshold: 2,
+        };
+        let spaces: Spaces = "L.L.L..L".parse().unwrap();
+        let spaces = spaces.step_with(&rule);
+        assert_eq!("#.#.#..#", spaces.to_string());
+        let spaces = spaces.step_with(&rule);
+        // Only the middle seat can see two people; the right one can't see 3 spaces away
+        assert_eq!("#.L.#..#", spaces.to_string());
+    }
+
+    #[test]
+    fn test_torus() {
+        let input = "L...#\n.....\n.....";
+        let spaces: Spaces = input.parse().unwrap();
+        let torus = Spaces::parse_with(input, Topology::torus()).unwrap();
+        let count = |spaces: &Spaces, neighbourhood| {
+            spaces.count_occupied_neighbours(0, 0, neighbourhood, 8)
+        };
+        assert_eq!(0, count(&spaces, Neighbourhood::Adjacent));
+        assert_eq!(1, count(&torus, Neighbourhood::Adjacent));
+        assert_eq!(1, count(&spaces, Neighbourhood::LineOfSight));
+        // Every diagonal wraps around until it gets to the far seat. Up and down see nobody
+        assert_eq!(6, count(&torus, Neighbourhood::LineOfSight));
+        // A ray that wraps all the way around without seeing a seat gives up
+        let empty = Spaces::parse_with("L....\n.....\n.....", Topology::torus()).unwrap();
+        assert_eq!(0, count(&empty, Neighbourhood::LineOfSight));
+        assert_eq!(
+            0,
+            empty.neighbours(0, 0, Neighbourhood::LineOfSight).count()
+        );
+    }
+
+    #[test]
+    fn test_von_neumann() {
+        let input = "###\n#L#\n###";
+        let spaces: Spaces = input.parse().unwrap();
+        let von_neumann = Spaces::parse_with(input, Topology::von_neumann()).unwrap();
+        assert_eq!(
+            8,
+            spaces.count_occupied_neighbours(1, 1, Neighbourhood::Adjacent, 8)
+        );
+        assert_eq!(
+            4,
+            von_neumann.count_occupied_neighbours(1, 1, Neighbourhood::Adjacent, 8)
+        );
+        assert_eq!(4, von_neumann.adjacent(1, 1).len());
+        assert_eq!(2, von_neumann.adjacent(0, 0).len());
+        assert_eq!(
+            4,
+            von_neumann
+                .with_topology(Topology::von_neumann().wrapping())
+                .adjacent(0, 0)
+                .len()
+        );
+    }
+
+    #[test]
+    fn test_hexagonal() {
+        let input = "# # L\n # L #\n  L # #";
+        let hex = Spaces::parse_with(input, Topology::hexagonal()).unwrap();
+        // The top left and bottom right corners aren't touching the middle on a hex grid
+        assert_eq!(
+            4,
+            hex.count_occupied_neighbours(1, 1, Neighbourhood::Adjacent, 8)
+        );
+        assert_eq!(6, hex.adjacent(1, 1).len());
+        let square = hex.clone().with_topology(Topology::rectangle());
+        assert_eq!(
+            6,
+            square.count_occupied_neighbours(1, 1, Neighbourhood::Adjacent, 8)
+        );
+        // Looking along a hex row from the corner
+        let hex =
+            Spaces::parse_with("L . . #\n . . . .\n  # . . .", Topology::hexagonal()).unwrap();
+        assert_eq!(
+            2,
+            hex.count_occupied_neighbours(0, 0, Neighbourhood::LineOfSight, 8)
+        );
+        assert_eq!(
+            0,
+            hex.count_occupied_neighbours(0, 0, Neighbourhood::Adjacent, 8)
+        );
+    }
+
     #[test]
     fn test_step_part_2() {
-        let expected_steps = vec![
+        let expected_steps = [
             "L.LL.LL.LL
 LLLLLLL.LL
 L.L.L..L..
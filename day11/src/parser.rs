@@ -1,4 +1,4 @@
-use crate::{Space, Spaces};
+use crate::{Layout, Space, Spaces, Topology};
 
 impl std::str::FromStr for Spaces {
     type Err = parse_display::ParseError;
@@ -14,9 +14,34 @@ impl std::str::FromStr for Spaces {
     }
 }
 
+impl Spaces {
+    /// Reads a room with a particular shape.
+    ///
+    /// Square rooms use the normal format. Hexagonal rooms have a space between each seat, and
+    /// each row is indented one more than the one above (the indent is optional), eg.
+    ///
+    /// ```text
+    /// L . L
+    ///  L L .
+    ///   . L L
+    /// ```
+    pub fn parse_with(s: &str, topology: Topology) -> Result<Self, parse_display::ParseError> {
+        let spaces: Self = match topology.layout {
+            Layout::Hexagonal => s
+                .lines()
+                .map(|line| line.split_whitespace().collect::<String>())
+                .collect::<Vec<String>>()
+                .join("\n")
+                .parse()?,
+            _ => s.parse()?,
+        };
+        Ok(spaces.with_topology(topology))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::Space;
+    use crate::{Space, Spaces, Topology};
 
     #[test]
     fn test_parser() {
@@ -37,4 +62,19 @@ L.LLLLL.LL"#;
         assert_eq!(got.get(1, 0), Some(&Space::Floor));
         assert_eq!(got.get(3, 1), Some(&Space::OccupiedSeat));
     }
+
+    #[test]
+    fn test_parse_hexagonal() {
+        let input = "# # L\n # L #\n  L # #";
+        let got = Spaces::parse_with(input, Topology::hexagonal()).unwrap();
+        assert_eq!(got.width(), 3);
+        assert_eq!(got.height(), 3);
+        assert_eq!(got.get(2, 0), Some(&Space::EmptySeat));
+        assert_eq!(got.get(0, 2), Some(&Space::EmptySeat));
+        assert_eq!(got.topology(), Topology::hexagonal());
+        assert_eq!(got.to_string(), input);
+        // The indent is optional
+        let unindented = Spaces::parse_with("# # L\n# L #\nL # #", Topology::hexagonal()).unwrap();
+        assert_eq!(unindented, got);
+    }
 }
@@ -1,2 +1,5 @@
 mod model;
-pub use model::{part1, part2, Direction, Pointing};
+pub use model::{
+    free, navigate, part1, part2, simplify, BoundingBox, Direction, Metric, NavigationError,
+    Navigator, Pointing, Position, RouteStats, ShipModel, Track, Voyage,
+};
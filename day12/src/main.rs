@@ -1,19 +1,81 @@
-use std::fs::read_to_string;
+use std::{
+    env::args,
+    fs::{read_to_string, write},
+};
 
-use day12::Direction;
+use day12::{
+    navigate, part1, part2, simplify, Direction, Metric, NavigationError, Navigator, ShipModel,
+    Track,
+};
+
+/// Records the route for one part of the puzzle
+fn record(part: &str, directions: &[Direction]) -> Result<Track, NavigationError> {
+    match part {
+        "1" => Track::record(&mut part1::State::default(), directions),
+        _ => Track::record(&mut part2::State::default(), directions),
+    }
+}
 
 fn main() {
     let input = read_to_string("input.txt").expect("Couldn't read input.txt");
     let directions: Vec<Direction> = input.lines().map(|line| line.parse().unwrap()).collect();
-    let mut ship: day12::part1::State = Default::default();
-    for direction in &directions {
-        ship.process_instruction(direction)
+    let mut args = args().skip(1);
+    let command = args.next();
+    // `day12 stats` describes the routes
+    if command.as_deref() == Some("stats") {
+        for part in &["1", "2"] {
+            let track = record(part, &directions).expect("Ship couldn't follow the directions");
+            for metric in &[Metric::Manhattan, Metric::Chebyshev, Metric::Euclidean] {
+                let stats = track
+                    .stats(*metric)
+                    .expect("The track always has the start");
+                println!(
+                    "Day 12 - Part {} - {:?}: travelled {:.1}, furthest {} at {:.1}, inside {} to {}",
+                    part,
+                    metric,
+                    stats.travelled,
+                    stats.furthest,
+                    stats.furthest_distance,
+                    stats.bounding_box.min,
+                    stats.bounding_box.max
+                );
+            }
+        }
+        return;
     }
+    // `day12 simplify` shows how much shorter the program can get
+    if command.as_deref() == Some("simplify") {
+        for (part, model) in &[(1, ShipModel::Part1), (2, ShipModel::Part2)] {
+            let simplified = simplify(&directions, *model).expect("Couldn't simplify the program");
+            println!(
+                "Day 12 - Part {} - Simplified {} instructions to {}",
+                part,
+                directions.len(),
+                simplified.len()
+            );
+        }
+        return;
+    }
+    // `day12 svg <file> [part]` or `day12 geojson <file> [part]` draws the route
+    if let Some(format) = command {
+        let path = args
+            .next()
+            .expect("Which file should I write the route to?");
+        let part = args.next().unwrap_or_else(|| "2".to_string());
+        let track = record(&part, &directions).expect("Ship couldn't follow the directions");
+        let output = match format.as_str() {
+            "svg" => track.to_svg(),
+            "geojson" => track.to_geojson(),
+            other => panic!("Unknown format {}: try svg or geojson", other),
+        };
+        write(&path, output).expect("Couldn't write the route");
+        return;
+    }
+    let mut ship = part1::State::default();
+    navigate(&mut ship, &directions).expect("Ship couldn't follow the directions");
     println!("Day 12 - Part 1 - Distance: {}", ship.distance());
     // Part 2
-    let mut ship: day12::part2::State = Default::default();
-    for direction in &directions {
-        ship.process_instruction(direction)
-    }
+    let mut ship = part2::State::default();
+    navigate(&mut ship, &directions).expect("Ship couldn't follow the directions");
     println!("Day 12 - Part 2 - Distance: {}", ship.distance());
 }
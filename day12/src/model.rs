@@ -1,9 +1,28 @@
 use parse_display::{Display, FromStr};
 
+pub mod free;
 pub mod part1;
 pub mod part2;
 
-#[derive(Display, FromStr, PartialEq, Debug, Clone, Copy)]
+#[cfg(test)]
+mod fixtures;
+
+mod error;
+pub use error::NavigationError;
+
+mod navigator;
+pub use navigator::{navigate, Navigator, Voyage};
+
+mod position;
+pub use position::{BoundingBox, Metric, Position, RouteStats};
+
+mod simplify;
+pub use simplify::{simplify, ShipModel};
+
+mod track;
+pub use track::Track;
+
+#[derive(Display, FromStr, PartialEq, Eq, Debug, Clone, Copy)]
 pub enum Direction {
     // Move (but don't turn North)
     #[display("N{0}")]
@@ -14,10 +33,10 @@ pub enum Direction {
     East(i64),
     #[display("W{0}")]
     West(i64),
-    // Turn left .0 degrees (always 90, 180, 270)
+    // Turn left .0 degrees (usually 90, 180, 270, but any multiple of 90 works)
     #[display("L{0}")]
     Left(i64),
-    // Turn right .0 degrees (always 90, 180, 270)
+    // Turn right .0 degrees (usually 90, 180, 270, but any multiple of 90 works)
     #[display("R{0}")]
     Right(i64),
     // Move Forward `n` spaces
@@ -25,16 +44,50 @@ pub enum Direction {
     Forward(i64),
 }
 
+impl Direction {
+    /// For turns, how many degrees clockwise we turn. Left turns are negative
+    pub fn clockwise_degrees(&self) -> Option<i64> {
+        match *self {
+            Direction::Left(degrees) => Some(-degrees),
+            Direction::Right(degrees) => Some(degrees),
+            _ => None,
+        }
+    }
+
+    /// For turns, how many quarter turns clockwise we end up turning (0 to 3).
+    /// Turns that aren't a multiple of 90 degrees are an error
+    pub fn quarter_turns(&self) -> Option<Result<u8, NavigationError>> {
+        let degrees = self.clockwise_degrees()?;
+        Some(if degrees % 90 == 0 {
+            // rem_euclid keeps negative (left) turns between 0 and 3
+            Ok((degrees / 90).rem_euclid(4) as u8)
+        } else {
+            Err(NavigationError::UnsupportedTurn(*self))
+        })
+    }
+}
+
 /// Which way the ship is currently pointing
-#[derive(Display, PartialEq, Debug)]
+#[derive(Display, PartialEq, Debug, Default)]
 pub enum Pointing {
     North,
     South,
+    #[default]
     East,
     West,
 }
 
 impl Pointing {
+    /// The way one step forward moves us, as (dx, dy)
+    pub fn vector(&self) -> (i64, i64) {
+        match self {
+            Pointing::North => (0, -1),
+            Pointing::South => (0, 1),
+            Pointing::East => (1, 0),
+            Pointing::West => (-1, 0),
+        }
+    }
+
     /// Turn right
     fn right(&mut self) {
         use Pointing::*;
@@ -55,6 +108,15 @@ impl Pointing {
             West => South,
         }
     }
+    /// Turn right `quarter_turns` times
+    fn turn(&mut self, quarter_turns: u8) {
+        match quarter_turns % 4 {
+            1 => self.right(),
+            2 => self.reverse(),
+            3 => self.left(),
+            _ => (),
+        }
+    }
     /// 180 degrees
     fn reverse(&mut self) {
         use Pointing::*;
@@ -67,16 +129,10 @@ impl Pointing {
     }
 }
 
-impl Default for Pointing {
-    fn default() -> Self {
-        Pointing::East
-    }
-}
-
 #[cfg(test)]
 mod tests {
 
-    use super::Direction;
+    use super::{Direction, NavigationError};
 
     #[test]
     fn test_parse() {
@@ -95,4 +151,20 @@ F11";
         ];
         assert_eq!(expected, got);
     }
+
+    #[test]
+    fn test_quarter_turns() {
+        let turns = |direction: Direction| direction.quarter_turns().unwrap();
+        assert_eq!(turns(Direction::Right(90)), Ok(1));
+        assert_eq!(turns(Direction::Left(90)), Ok(3));
+        assert_eq!(turns(Direction::Right(360)), Ok(0));
+        assert_eq!(turns(Direction::Left(450)), Ok(3));
+        assert_eq!(turns(Direction::Right(-90)), Ok(3));
+        assert_eq!(turns(Direction::Left(-180)), Ok(2));
+        assert_eq!(
+            turns(Direction::Right(45)),
+            Err(NavigationError::UnsupportedTurn(Direction::Right(45)))
+        );
+        assert_eq!(Direction::Forward(90).quarter_turns(), None);
+    }
 }
@@ -0,0 +1,39 @@
+//! Things that can go wrong while navigating
+
+use std::{error::Error, fmt};
+
+use super::{Direction, Position};
+
+/// An instruction that a ship model can't follow
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NavigationError {
+    /// The ship can only turn in multiples of 90 degrees
+    UnsupportedTurn(Direction),
+    /// A simplified program didn't take the ship to the same place as the original
+    SimplifiedRouteDiffers {
+        original: Position,
+        simplified: Position,
+    },
+}
+
+impl fmt::Display for NavigationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NavigationError::UnsupportedTurn(direction) => write!(
+                f,
+                "Can't follow {}: turns must be a multiple of 90 degrees",
+                direction
+            ),
+            NavigationError::SimplifiedRouteDiffers {
+                original,
+                simplified,
+            } => write!(
+                f,
+                "The simplified program ends at {} instead of {}",
+                simplified, original
+            ),
+        }
+    }
+}
+
+impl Error for NavigationError {}
@@ -0,0 +1,20 @@
+//! Inputs shared between the model tests
+
+use crate::{Direction, Position};
+
+pub fn positions(points: &[(i64, i64)]) -> Vec<Position> {
+    points.iter().cloned().map(Position::from).collect()
+}
+
+/// Directions separated by newlines or spaces
+pub fn parse(input: &str) -> Vec<Direction> {
+    input
+        .split_whitespace()
+        .map(|line| line.parse().unwrap())
+        .collect()
+}
+
+/// The example from the puzzle
+pub fn directions() -> Vec<Direction> {
+    parse("F10\nN3\nF7\nR90\nF11")
+}
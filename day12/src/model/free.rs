@@ -0,0 +1,249 @@
+//! Ships that can turn any number of degrees, not just multiples of 90
+//!
+//! Positions are kept as floating point numbers, and only turned into whole numbers (using a
+//! `Rounding` rule) when someone asks for them through `Navigator`.
+
+use super::{Direction, NavigationError, Navigator, Position};
+
+/// Anything this close to a whole number counts as that whole number, so the tiny errors that
+/// come out of sin and cos don't push a ship over a line
+const EPSILON: f64 = 1e-9;
+
+/// How to turn a floating point coordinate into a whole number
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rounding {
+    /// Round to the closest whole number (halves go away from zero)
+    #[default]
+    Nearest,
+    /// Round towards negative infinity (north and west)
+    Down,
+    /// Round towards positive infinity (south and east)
+    Up,
+    /// Drop the fraction, rounding towards the origin
+    TowardZero,
+}
+
+impl Rounding {
+    pub fn round(&self, value: f64) -> i64 {
+        let nearest = value.round();
+        if (value - nearest).abs() < EPSILON {
+            return nearest as i64;
+        }
+        let rounded = match self {
+            Rounding::Nearest => nearest,
+            Rounding::Down => value.floor(),
+            Rounding::Up => value.ceil(),
+            Rounding::TowardZero => value.trunc(),
+        };
+        rounded as i64
+    }
+
+    fn round_point(&self, (x, y): (f64, f64)) -> Position {
+        Position::new(self.round(x), self.round(y))
+    }
+}
+
+/// Rotates (x, y) clockwise around the origin (east and south are positive).
+/// Quarter turns are done exactly, so they give the same answers as the whole number ships
+fn rotate((x, y): (f64, f64), degrees: f64) -> (f64, f64) {
+    let degrees = degrees.rem_euclid(360.0);
+    if degrees == 0.0 {
+        (x, y)
+    } else if degrees == 90.0 {
+        (-y, x)
+    } else if degrees == 180.0 {
+        (-x, -y)
+    } else if degrees == 270.0 {
+        (y, -x)
+    } else {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        (x * cos - y * sin, x * sin + y * cos)
+    }
+}
+
+/// A ship following the part 1 rules (turns steer the ship), but able to turn any angle
+#[derive(Debug, Clone, PartialEq)]
+pub struct State {
+    /// Where one `F1` takes the ship. Always 1 unit long
+    heading: (f64, f64),
+    x: f64,
+    y: f64,
+    rounding: Rounding,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State::new(Rounding::default())
+    }
+}
+
+impl State {
+    /// A ship at the origin, pointing east
+    pub fn new(rounding: Rounding) -> State {
+        State {
+            heading: (1.0, 0.0),
+            x: 0.0,
+            y: 0.0,
+            rounding,
+        }
+    }
+
+    /// Where the ship is, without any rounding
+    pub fn exact_position(&self) -> (f64, f64) {
+        (self.x, self.y)
+    }
+}
+
+impl Navigator for State {
+    fn process_instruction(&mut self, direction: &Direction) -> Result<(), NavigationError> {
+        match *direction {
+            Direction::North(n) => self.y -= n as f64,
+            Direction::South(n) => self.y += n as f64,
+            Direction::East(n) => self.x += n as f64,
+            Direction::West(n) => self.x -= n as f64,
+            Direction::Left(_) | Direction::Right(_) => {
+                if let Some(degrees) = direction.clockwise_degrees() {
+                    self.heading = rotate(self.heading, degrees as f64);
+                }
+            }
+            Direction::Forward(n) => {
+                self.x += self.heading.0 * n as f64;
+                self.y += self.heading.1 * n as f64;
+            }
+        }
+        Ok(())
+    }
+
+    fn position(&self) -> Position {
+        self.rounding.round_point((self.x, self.y))
+    }
+
+    fn heading(&self) -> Position {
+        self.rounding.round_point(self.heading)
+    }
+}
+
+/// A ship following the part 2 rules (turns swing the waypoint around), but able to turn any angle
+#[derive(Debug, Clone, PartialEq)]
+pub struct WaypointState {
+    /// The waypoint, relative to the ship
+    waypoint: (f64, f64),
+    x: f64,
+    y: f64,
+    rounding: Rounding,
+}
+
+impl Default for WaypointState {
+    fn default() -> Self {
+        WaypointState::new(Rounding::default())
+    }
+}
+
+impl WaypointState {
+    /// A ship at the origin, with the waypoint 10 east and 1 north
+    pub fn new(rounding: Rounding) -> WaypointState {
+        WaypointState {
+            waypoint: (10.0, -1.0),
+            x: 0.0,
+            y: 0.0,
+            rounding,
+        }
+    }
+
+    /// Where the ship is, without any rounding
+    pub fn exact_position(&self) -> (f64, f64) {
+        (self.x, self.y)
+    }
+}
+
+impl Navigator for WaypointState {
+    fn process_instruction(&mut self, direction: &Direction) -> Result<(), NavigationError> {
+        match *direction {
+            Direction::North(n) => self.waypoint.1 -= n as f64,
+            Direction::South(n) => self.waypoint.1 += n as f64,
+            Direction::East(n) => self.waypoint.0 += n as f64,
+            Direction::West(n) => self.waypoint.0 -= n as f64,
+            Direction::Left(_) | Direction::Right(_) => {
+                if let Some(degrees) = direction.clockwise_degrees() {
+                    self.waypoint = rotate(self.waypoint, degrees as f64);
+                }
+            }
+            Direction::Forward(n) => {
+                self.x += self.waypoint.0 * n as f64;
+                self.y += self.waypoint.1 * n as f64;
+            }
+        }
+        Ok(())
+    }
+
+    fn position(&self) -> Position {
+        self.rounding.round_point((self.x, self.y))
+    }
+
+    fn heading(&self) -> Position {
+        self.rounding.round_point(self.waypoint)
+    }
+
+    fn waypoint(&self) -> Option<Position> {
+        Some(
+            self.rounding
+                .round_point((self.x + self.waypoint.0, self.y + self.waypoint.1)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Rounding, State, WaypointState};
+    use crate::model::fixtures::parse;
+    use crate::{navigate, part1, part2, Navigator, Position};
+
+    #[test]
+    fn test_matches_quarter_turns() {
+        let directions = parse("F10\nN3\nF7\nR90\nF11\nL270\nF3\nR-90\nW4\nF2");
+        let expected = navigate(&mut part1::State::default(), &directions).unwrap();
+        let got = navigate(&mut State::default(), &directions).unwrap();
+        assert_eq!(got, expected);
+        let expected = navigate(&mut part2::State::default(), &directions).unwrap();
+        let got = navigate(&mut WaypointState::default(), &directions).unwrap();
+        assert_eq!(got, expected);
+    }
+
+    #[test]
+    fn test_rounding() {
+        let directions = parse("R45\nF10");
+        let position = |rounding| {
+            let mut ship = State::new(rounding);
+            navigate(&mut ship, &directions).unwrap().position
+        };
+        // 10 at 45 degrees is about 7.07 east and 7.07 south
+        assert_eq!(position(Rounding::Nearest), Position::new(7, 7));
+        assert_eq!(position(Rounding::Down), Position::new(7, 7));
+        assert_eq!(position(Rounding::Up), Position::new(8, 8));
+        let mut ship = State::default();
+        navigate(&mut ship, &parse("R150\nF20")).unwrap();
+        let (x, y) = ship.exact_position();
+        assert!((x + 17.32).abs() < 0.01 && (y - 10.0).abs() < 0.01);
+        ship.rounding = Rounding::TowardZero;
+        assert_eq!(ship.position(), Position::new(-17, 10));
+        ship.rounding = Rounding::Down;
+        assert_eq!(ship.position(), Position::new(-18, 10));
+    }
+
+    #[test]
+    fn test_noise_is_ignored() {
+        // Two 45 degree turns leave the heading a tiny bit off due south
+        let mut ship = State::new(Rounding::Down);
+        navigate(&mut ship, &parse("R45\nR45\nF10")).unwrap();
+        assert_eq!(ship.position(), Position::new(0, 10));
+        assert_eq!(ship.heading(), Position::new(0, 1));
+    }
+
+    #[test]
+    fn test_waypoint() {
+        let mut ship = WaypointState::default();
+        navigate(&mut ship, &parse("S1\nL45\nF1")).unwrap();
+        // The waypoint (10, 0) swings up to about (7.07, -7.07)
+        assert_eq!(ship.position(), Position::new(7, -7));
+    }
+}
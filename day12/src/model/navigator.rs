@@ -0,0 +1,101 @@
+//! One interface for both ship models, so the same code can drive either of them
+
+use super::{Direction, NavigationError, Position};
+
+/// Something that can follow navigation instructions
+pub trait Navigator {
+    /// Update our state. If the ship can't follow the instruction, it's left where it was
+    fn process_instruction(&mut self, direction: &Direction) -> Result<(), NavigationError>;
+
+    /// Where the ship is
+    fn position(&self) -> Position;
+
+    /// Where one `F1` would take the ship, relative to where it is now
+    fn heading(&self) -> Position;
+
+    /// Manhattan distance from our origin
+    fn distance(&self) -> i64 {
+        self.position().manhattan()
+    }
+
+    /// Where the waypoint is, in the same coordinates as `position`, for ships that have one
+    fn waypoint(&self) -> Option<Position> {
+        None
+    }
+}
+
+/// What happened when a ship followed a list of instructions
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Voyage {
+    /// Where the ship ended up
+    pub position: Position,
+    /// Which way the ship ended up heading (see `Navigator::heading`)
+    pub heading: Position,
+    /// Where the ship was after each instruction
+    pub positions: Vec<Position>,
+}
+
+/// Runs every instruction on the ship, keeping track of where it goes.
+/// Stops at the first instruction the ship can't follow
+pub fn navigate<N: Navigator>(
+    ship: &mut N,
+    directions: &[Direction],
+) -> Result<Voyage, NavigationError> {
+    let positions = directions
+        .iter()
+        .map(|direction| {
+            ship.process_instruction(direction)?;
+            Ok(ship.position())
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(Voyage {
+        position: ship.position(),
+        heading: ship.heading(),
+        positions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{navigate, Navigator, Voyage};
+    use crate::model::fixtures::{directions, positions};
+    use crate::{part1, part2, Direction, NavigationError, Position};
+
+    #[test]
+    fn test_part1() {
+        let mut ship = part1::State::default();
+        let voyage = navigate(&mut ship, &directions()).unwrap();
+        let expected = Voyage {
+            position: Position::new(17, 8),
+            heading: Position::new(0, 1),
+            positions: positions(&[(10, 0), (10, -3), (17, -3), (17, -3), (17, 8)]),
+        };
+        assert_eq!(voyage, expected);
+        assert_eq!(ship.distance(), 25);
+    }
+
+    #[test]
+    fn test_part2() {
+        let mut ship = part2::State::default();
+        let voyage = navigate(&mut ship, &directions()).unwrap();
+        let expected = Voyage {
+            position: Position::new(214, 72),
+            heading: Position::new(4, 10),
+            positions: positions(&[(100, -10), (100, -10), (170, -38), (170, -38), (214, 72)]),
+        };
+        assert_eq!(voyage, expected);
+        assert_eq!(ship.distance(), 286);
+    }
+
+    #[test]
+    fn test_bad_turn() {
+        let mut ship = part1::State::default();
+        let directions: Vec<Direction> = vec![Direction::Forward(1), Direction::Left(30)];
+        let got = navigate(&mut ship, &directions);
+        assert_eq!(
+            got,
+            Err(NavigationError::UnsupportedTurn(Direction::Left(30)))
+        );
+        assert_eq!(ship.position(), Position::new(1, 0));
+    }
+}
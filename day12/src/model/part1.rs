@@ -1,4 +1,4 @@
-use super::{Direction, Pointing};
+use super::{Direction, NavigationError, Navigator, Pointing, Position};
 
 #[derive(Default, PartialEq, Debug)]
 /// The state of the ship
@@ -10,33 +10,34 @@ pub struct State {
     y: i64,
 }
 
-impl State {
-    /// manhattan distance from our origin
-    pub fn distance(&self) -> i64 {
-        self.x + self.y
+impl Navigator for State {
+    fn position(&self) -> Position {
+        Position::new(self.x, self.y)
+    }
+
+    fn heading(&self) -> Position {
+        self.pointing.vector().into()
     }
 
     /// Update our state
-    pub fn process_instruction(&mut self, direction: &Direction) {
+    fn process_instruction(&mut self, direction: &Direction) -> Result<(), NavigationError> {
         match direction {
             Direction::North(n) => self.y -= n,
             Direction::South(n) => self.y += n,
             Direction::East(n) => self.x += n,
             Direction::West(n) => self.x -= n,
-            Direction::Left(90) => self.pointing.left(),
-            Direction::Left(180) => self.pointing.reverse(),
-            Direction::Left(270) => self.pointing.right(),
-            Direction::Right(90) => self.pointing.right(),
-            Direction::Right(180) => self.pointing.reverse(),
-            Direction::Right(270) => self.pointing.left(),
-            Direction::Forward(n) => match self.pointing {
-                Pointing::North => self.process_instruction(&Direction::North(*n)),
-                Pointing::South => self.process_instruction(&Direction::South(*n)),
-                Pointing::East => self.process_instruction(&Direction::East(*n)),
-                Pointing::West => self.process_instruction(&Direction::West(*n)),
-            },
-            other => unreachable!("Unexpected instruction: {:?}", other),
+            Direction::Left(_) | Direction::Right(_) => {
+                if let Some(quarter_turns) = direction.quarter_turns() {
+                    self.pointing.turn(quarter_turns?)
+                }
+            }
+            Direction::Forward(n) => {
+                let (dx, dy) = self.pointing.vector();
+                self.x += dx * n;
+                self.y += dy * n;
+            }
         }
+        Ok(())
     }
 }
 
@@ -44,7 +45,7 @@ impl State {
 mod tests {
 
     use super::State;
-    use crate::model::{Direction, Pointing};
+    use crate::model::{Direction, Navigator, Pointing};
 
     #[test]
     fn test_steering() {
@@ -54,29 +55,50 @@ mod tests {
         assert_eq!(Pointing::East, ship.pointing);
         // Check each direction
         // Forward 10
-        ship.process_instruction(&Direction::Forward(10));
+        ship.process_instruction(&Direction::Forward(10)).unwrap();
         assert_eq!(10, ship.x);
         assert_eq!(0, ship.y);
         assert_eq!(Pointing::East, ship.pointing);
         // North 3
-        ship.process_instruction(&Direction::North(3));
+        ship.process_instruction(&Direction::North(3)).unwrap();
         assert_eq!(10, ship.x);
         assert_eq!(-3, ship.y);
         assert_eq!(Pointing::East, ship.pointing);
         // Forward 7 (still facing east)
-        ship.process_instruction(&Direction::Forward(7));
+        ship.process_instruction(&Direction::Forward(7)).unwrap();
         assert_eq!(17, ship.x);
         assert_eq!(-3, ship.y);
         assert_eq!(Pointing::East, ship.pointing);
         // Right 90 (now facing south)
-        ship.process_instruction(&Direction::Right(90));
+        ship.process_instruction(&Direction::Right(90)).unwrap();
         assert_eq!(17, ship.x);
         assert_eq!(-3, ship.y);
         assert_eq!(Pointing::South, ship.pointing);
         // Forward 11 (now facing south)
-        ship.process_instruction(&Direction::Forward(11));
+        ship.process_instruction(&Direction::Forward(11)).unwrap();
         assert_eq!(17, ship.x);
         assert_eq!(8, ship.y);
         assert_eq!(Pointing::South, ship.pointing);
     }
+
+    #[test]
+    fn test_distance_north_west() {
+        let mut ship: State = Default::default();
+        ship.process_instruction(&Direction::West(5)).unwrap();
+        ship.process_instruction(&Direction::North(3)).unwrap();
+        assert_eq!(8, ship.distance());
+    }
+
+    #[test]
+    fn test_big_turns() {
+        let mut ship: State = Default::default();
+        ship.process_instruction(&Direction::Left(450)).unwrap();
+        assert_eq!(Pointing::North, ship.pointing);
+        ship.process_instruction(&Direction::Right(-180)).unwrap();
+        assert_eq!(Pointing::South, ship.pointing);
+        ship.process_instruction(&Direction::Right(360)).unwrap();
+        assert_eq!(Pointing::South, ship.pointing);
+        assert!(ship.process_instruction(&Direction::Right(100)).is_err());
+        assert_eq!(Pointing::South, ship.pointing);
+    }
 }
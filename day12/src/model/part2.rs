@@ -1,6 +1,6 @@
 use std::mem::swap;
 
-use super::{Direction, Pointing};
+use super::{Direction, NavigationError, Navigator, Pointing, Position};
 
 #[derive(PartialEq, Debug)]
 /// The state of the ship
@@ -28,14 +28,22 @@ impl Default for State {
     }
 }
 
-impl State {
-    /// Manhattan distance from origin
-    pub fn distance(&self) -> usize {
-        (self.x.abs() + self.y.abs()) as usize
+impl Navigator for State {
+    fn position(&self) -> Position {
+        Position::new(self.x, self.y)
+    }
+
+    /// The ship always moves towards the waypoint
+    fn heading(&self) -> Position {
+        Position::new(self.way_x, self.way_y)
+    }
+
+    fn waypoint(&self) -> Option<Position> {
+        Some(Position::new(self.x + self.way_x, self.y + self.way_y))
     }
 
     /// Process one instruction and move the ship or the waypoint
-    pub fn process_instruction(&mut self, direction: &Direction) {
+    fn process_instruction(&mut self, direction: &Direction) -> Result<(), NavigationError> {
         match direction {
             // Move the waypoint / direction finder North
             Direction::North(n) => self.way_y -= n,
@@ -43,32 +51,39 @@ impl State {
             Direction::East(n) => self.way_x += n,
             Direction::West(n) => self.way_x -= n,
             // Rotate the waypoint around the ship
-            Direction::Left(270) | Direction::Right(90) => {
-                swap(&mut self.way_x, &mut self.way_y);
-                self.way_x = -self.way_x;
-            }
-            Direction::Left(180) | Direction::Right(180) => {
-                self.way_x = -self.way_x;
-                self.way_y = -self.way_y;
-            }
-            Direction::Left(90) | Direction::Right(270) => {
-                swap(&mut self.way_x, &mut self.way_y);
-                self.way_y = -self.way_y;
+            Direction::Left(_) | Direction::Right(_) => {
+                if let Some(quarter_turns) = direction.quarter_turns() {
+                    match quarter_turns? {
+                        1 => {
+                            swap(&mut self.way_x, &mut self.way_y);
+                            self.way_x = -self.way_x;
+                        }
+                        2 => {
+                            self.way_x = -self.way_x;
+                            self.way_y = -self.way_y;
+                        }
+                        3 => {
+                            swap(&mut self.way_x, &mut self.way_y);
+                            self.way_y = -self.way_y;
+                        }
+                        _ => (),
+                    }
+                }
             }
             Direction::Forward(n) => {
                 // Move forward multiples of the waypoint
                 self.x += self.way_x * n;
                 self.y += self.way_y * n;
             }
-            other => unreachable!("Unexpected instruction: {:?}", other),
         }
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
 
-    use crate::Direction;
+    use crate::{Direction, NavigationError, Navigator};
 
     use super::State;
 
@@ -79,12 +94,12 @@ mod tests {
         // F10 moves the ship to the waypoint 10 times (a total of 100 units east
         // and 10 units north), leaving the ship at east 100, north 10. The
         // waypoint stays 10 units east and 1 unit north of the ship.
-        ship.process_instruction(&Direction::Forward(10));
+        ship.process_instruction(&Direction::Forward(10)).unwrap();
         assert_eq!(ship.x, 100);
         assert_eq!(ship.y, -10);
         // N3 moves the waypoint 3 units north to 10 units east and 4 units north
         // of the ship. The ship remains at east 100, north 10.
-        ship.process_instruction(&Direction::North(3));
+        ship.process_instruction(&Direction::North(3)).unwrap();
         assert_eq!(ship.x, 100);
         assert_eq!(ship.y, -10);
         assert_eq!(ship.way_x, 10);
@@ -92,7 +107,7 @@ mod tests {
         // F7 moves the ship to the waypoint 7 times (a total of 70 units east and
         // 28 units north), leaving the ship at east 170, north 38. The waypoint
         // stays 10 units east and 4 units north of the ship.
-        ship.process_instruction(&Direction::Forward(7));
+        ship.process_instruction(&Direction::Forward(7)).unwrap();
         assert_eq!(ship.x, 170);
         assert_eq!(ship.y, -38);
         assert_eq!(ship.way_x, 10);
@@ -100,7 +115,7 @@ mod tests {
         // R90 rotates the waypoint around the ship clockwise 90 degrees, moving
         // it to 4 units east and 10 units south of the ship. The ship remains at
         // east 170, north 38.
-        ship.process_instruction(&Direction::Right(90));
+        ship.process_instruction(&Direction::Right(90)).unwrap();
         assert_eq!(ship.x, 170);
         assert_eq!(ship.y, -38);
         assert_eq!(ship.way_x, 4);
@@ -108,7 +123,7 @@ mod tests {
         // F11 moves the ship to the waypoint 11 times (a total of 44 units east
         // and 110 units south), leaving the ship at east 214, south 72. The
         // waypoint stays 4 units east and 10 units south of the ship.
-        ship.process_instruction(&Direction::Forward(11));
+        ship.process_instruction(&Direction::Forward(11)).unwrap();
         assert_eq!(ship.x, 214);
         assert_eq!(ship.y, 72);
         assert_eq!(ship.way_x, 4);
@@ -116,4 +131,20 @@ mod tests {
         // After these operations, the ship's Manhattan distance from its starting position is 214 + 72 = 286.
         assert_eq!(286, ship.distance());
     }
+
+    #[test]
+    fn test_big_turns() {
+        let mut ship: State = Default::default();
+        // 270 anticlockwise is the same as R90
+        ship.process_instruction(&Direction::Right(-270)).unwrap();
+        assert_eq!((ship.way_x, ship.way_y), (1, 10));
+        // So is 450 clockwise
+        ship.process_instruction(&Direction::Left(-450)).unwrap();
+        assert_eq!((ship.way_x, ship.way_y), (-10, 1));
+        assert_eq!(
+            ship.process_instruction(&Direction::Left(60)),
+            Err(NavigationError::UnsupportedTurn(Direction::Left(60)))
+        );
+        assert_eq!((ship.way_x, ship.way_y), (-10, 1));
+    }
 }
@@ -0,0 +1,188 @@
+//! Where things are on the sea, how far apart they are, and what a whole route looks like
+
+use std::fmt;
+
+/// A point on the sea, relative to where the ship started. East and south are positive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Position {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Position {
+    pub fn new(x: i64, y: i64) -> Position {
+        Position { x, y }
+    }
+
+    /// Distance from the origin, only moving along the compass directions
+    pub fn manhattan(&self) -> i64 {
+        self.x.abs() + self.y.abs()
+    }
+
+    /// Distance from the origin, if diagonal steps count as one (like a king in chess)
+    pub fn chebyshev(&self) -> i64 {
+        self.x.abs().max(self.y.abs())
+    }
+
+    /// Distance from the origin as the crow flies
+    pub fn euclidean(&self) -> f64 {
+        (self.x as f64).hypot(self.y as f64)
+    }
+
+    /// Distance to another position, measured with `metric`
+    pub fn distance_to(&self, other: Position, metric: Metric) -> f64 {
+        let offset = Position::new(other.x - self.x, other.y - self.y);
+        metric.length(offset)
+    }
+}
+
+impl From<(i64, i64)> for Position {
+    fn from((x, y): (i64, i64)) -> Position {
+        Position { x, y }
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/// A way of measuring distance
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    Manhattan,
+    Chebyshev,
+    Euclidean,
+}
+
+impl Metric {
+    /// How far `position` is from the origin
+    pub fn length(&self, position: Position) -> f64 {
+        match self {
+            Metric::Manhattan => position.manhattan() as f64,
+            Metric::Chebyshev => position.chebyshev() as f64,
+            Metric::Euclidean => position.euclidean(),
+        }
+    }
+}
+
+/// The smallest rectangle that holds every position on a route
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundingBox {
+    /// The north west corner
+    pub min: Position,
+    /// The south east corner
+    pub max: Position,
+}
+
+impl BoundingBox {
+    /// Returns None if there are no positions
+    pub fn around<'a, I>(positions: I) -> Option<BoundingBox>
+    where
+        I: IntoIterator<Item = &'a Position>,
+    {
+        let mut positions = positions.into_iter();
+        let first = *positions.next()?;
+        Some(positions.fold(
+            BoundingBox {
+                min: first,
+                max: first,
+            },
+            |bounds, position| BoundingBox {
+                min: Position::new(bounds.min.x.min(position.x), bounds.min.y.min(position.y)),
+                max: Position::new(bounds.max.x.max(position.x), bounds.max.y.max(position.y)),
+            },
+        ))
+    }
+
+    pub fn width(&self) -> i64 {
+        self.max.x - self.min.x
+    }
+
+    pub fn height(&self) -> i64 {
+        self.max.y - self.min.y
+    }
+}
+
+/// A summary of where a ship went
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteStats {
+    /// The area the ship stayed inside
+    pub bounding_box: BoundingBox,
+    /// The position furthest from the origin (the first one, if there's a tie)
+    pub furthest: Position,
+    /// How far `furthest` is from the origin
+    pub furthest_distance: f64,
+    /// The length of every leg of the route added up
+    pub travelled: f64,
+}
+
+impl RouteStats {
+    /// Works out the stats for a route, measuring every distance with `metric`.
+    /// Returns None if the route is empty
+    pub fn new(route: &[Position], metric: Metric) -> Option<RouteStats> {
+        let bounding_box = BoundingBox::around(route)?;
+        let mut furthest = route[0];
+        let mut furthest_distance = metric.length(furthest);
+        for &position in &route[1..] {
+            let distance = metric.length(position);
+            if distance > furthest_distance {
+                furthest = position;
+                furthest_distance = distance;
+            }
+        }
+        let travelled = route
+            .windows(2)
+            .map(|leg| leg[0].distance_to(leg[1], metric))
+            .sum();
+        Some(RouteStats {
+            bounding_box,
+            furthest,
+            furthest_distance,
+            travelled,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BoundingBox, Metric, Position, RouteStats};
+
+    #[test]
+    fn test_metrics() {
+        let position = Position::new(-3, 4);
+        assert_eq!(position.manhattan(), 7);
+        assert_eq!(position.chebyshev(), 4);
+        assert_eq!(position.euclidean(), 5.0);
+        let other = Position::new(1, 1);
+        assert_eq!(position.distance_to(other, Metric::Manhattan), 7.0);
+        assert_eq!(position.distance_to(other, Metric::Chebyshev), 4.0);
+        assert_eq!(position.distance_to(other, Metric::Euclidean), 5.0);
+    }
+
+    #[test]
+    fn test_stats() {
+        let route: Vec<Position> = vec![(0, 0), (10, 0), (10, -3), (-2, 2)]
+            .into_iter()
+            .map(Position::from)
+            .collect();
+        let stats = RouteStats::new(&route, Metric::Manhattan).unwrap();
+        let expected = RouteStats {
+            bounding_box: BoundingBox {
+                min: Position::new(-2, -3),
+                max: Position::new(10, 2),
+            },
+            furthest: Position::new(10, -3),
+            furthest_distance: 13.0,
+            travelled: 10.0 + 3.0 + 17.0,
+        };
+        assert_eq!(stats, expected);
+        assert_eq!(stats.bounding_box.width(), 12);
+        assert_eq!(stats.bounding_box.height(), 5);
+        let stats = RouteStats::new(&route, Metric::Chebyshev).unwrap();
+        assert_eq!(stats.furthest, Position::new(10, 0));
+        assert_eq!(stats.travelled, 10.0 + 3.0 + 12.0);
+        assert_eq!(RouteStats::new(&[], Metric::Euclidean), None);
+    }
+}
@@ -0,0 +1,211 @@
+//! Rewrites navigation programs into shorter ones that take the ship to the same place
+//!
+//! What counts as "the same" depends on the ship model. For part 1, compass moves never change
+//! where the ship is pointing, so they can all be pulled out and added up. For part 2 they move
+//! the waypoint, so only moves that sit next to each other can be merged.
+
+use super::{navigate, part1, part2, Direction, NavigationError, Navigator};
+
+/// Which set of rules to simplify a program for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShipModel {
+    /// Turns steer the ship, and compass moves push the ship around
+    Part1,
+    /// Turns swing the waypoint, and compass moves push the waypoint around
+    Part2,
+}
+
+/// One simplified step. Consecutive steps of the same kind get merged together
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    /// A compass move, as (dx, dy)
+    Move(i64, i64),
+    /// Quarter turns clockwise (0 to 3)
+    Turn(u8),
+    Forward(i64),
+}
+
+impl Step {
+    /// Returns the two steps merged into one, if they can be
+    fn merge(self, next: Step) -> Option<Step> {
+        match (self, next) {
+            (Step::Move(x1, y1), Step::Move(x2, y2)) => Some(Step::Move(x1 + x2, y1 + y2)),
+            (Step::Turn(a), Step::Turn(b)) => Some(Step::Turn((a + b) % 4)),
+            (Step::Forward(a), Step::Forward(b)) => Some(Step::Forward(a + b)),
+            _ => None,
+        }
+    }
+
+    /// True if the step doesn't do anything
+    fn is_nothing(&self) -> bool {
+        matches!(self, Step::Move(0, 0) | Step::Turn(0) | Step::Forward(0))
+    }
+
+    /// Adds the instructions for this step to `out`
+    fn write(&self, out: &mut Vec<Direction>) {
+        match *self {
+            Step::Move(dx, dy) => {
+                if dy < 0 {
+                    out.push(Direction::North(-dy));
+                } else if dy > 0 {
+                    out.push(Direction::South(dy));
+                }
+                if dx < 0 {
+                    out.push(Direction::West(-dx));
+                } else if dx > 0 {
+                    out.push(Direction::East(dx));
+                }
+            }
+            Step::Turn(1) => out.push(Direction::Right(90)),
+            Step::Turn(2) => out.push(Direction::Right(180)),
+            Step::Turn(3) => out.push(Direction::Left(90)),
+            Step::Turn(_) => (),
+            Step::Forward(0) => (),
+            Step::Forward(n) => out.push(Direction::Forward(n)),
+        }
+    }
+}
+
+/// Pushes a step, merging it into the last one if possible (and dropping it if they cancel out)
+fn push(steps: &mut Vec<Step>, step: Step) {
+    match steps.last().and_then(|&last| last.merge(step)) {
+        Some(merged) => {
+            steps.pop();
+            if !merged.is_nothing() {
+                steps.push(merged);
+            }
+        }
+        None if step.is_nothing() => (),
+        None => steps.push(step),
+    }
+}
+
+/// Rewrites `program` into a shorter one that leaves a `model` ship in the same place.
+///
+/// Consecutive compass moves are merged, turns are reduced to R90, R180 or L90 (or dropped if they
+/// cancel out), and consecutive forwards are folded together. For part 1, all the compass moves end
+/// up merged at the end. Both programs are replayed to make sure they really do end up in the same
+/// place, facing the same way
+pub fn simplify(
+    program: &[Direction],
+    model: ShipModel,
+) -> Result<Vec<Direction>, NavigationError> {
+    let mut steps = Vec::new();
+    // Part 1 compass moves can happen whenever, so we save them all up for the end
+    let mut moves = Step::Move(0, 0);
+    for direction in program {
+        let step = match *direction {
+            Direction::North(n) => Step::Move(0, -n),
+            Direction::South(n) => Step::Move(0, n),
+            Direction::East(n) => Step::Move(n, 0),
+            Direction::West(n) => Step::Move(-n, 0),
+            Direction::Forward(n) => Step::Forward(n),
+            // Everything else is a turn
+            _ => Step::Turn(direction.quarter_turns().unwrap_or(Ok(0))?),
+        };
+        match (model, step) {
+            (ShipModel::Part1, Step::Move(..)) => moves = moves.merge(step).unwrap_or(moves),
+            _ => push(&mut steps, step),
+        }
+    }
+    push(&mut steps, moves);
+    let mut simplified = Vec::with_capacity(steps.len() + 1);
+    for step in &steps {
+        step.write(&mut simplified);
+    }
+    match model {
+        ShipModel::Part1 => check(&mut part1::State::default(), program, &simplified)?,
+        ShipModel::Part2 => check(&mut part2::State::default(), program, &simplified)?,
+    }
+    Ok(simplified)
+}
+
+/// Makes sure both programs take the ship to the same place, facing the same way
+fn check<N: Navigator + Default>(
+    ship: &mut N,
+    original: &[Direction],
+    simplified: &[Direction],
+) -> Result<(), NavigationError> {
+    let expected = navigate(ship, original)?;
+    let got = navigate(&mut N::default(), simplified)?;
+    if (expected.position, expected.heading) == (got.position, got.heading) {
+        Ok(())
+    } else {
+        Err(NavigationError::SimplifiedRouteDiffers {
+            original: expected.position,
+            simplified: got.position,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{simplify, ShipModel};
+    use crate::model::fixtures::parse;
+    use crate::{Direction, NavigationError};
+
+    #[test]
+    fn test_example() {
+        let program = parse("F10 N3 F7 R90 F11");
+        assert_eq!(
+            simplify(&program, ShipModel::Part1).unwrap(),
+            parse("F17 R90 F11 N3")
+        );
+        // Nothing can move past a part 2 compass move
+        assert_eq!(simplify(&program, ShipModel::Part2).unwrap(), program);
+    }
+
+    #[test]
+    fn test_cancelling() {
+        let program = parse("R90 L90 F5 F5 N2 S5 E1 L180 R180 L360 F1 W1");
+        assert_eq!(
+            simplify(&program, ShipModel::Part1).unwrap(),
+            parse("F11 S3")
+        );
+        assert_eq!(
+            simplify(&program, ShipModel::Part2).unwrap(),
+            parse("F10 S3 E1 F1 W1")
+        );
+    }
+
+    #[test]
+    fn test_turns() {
+        let turns = |program| simplify(&parse(program), ShipModel::Part2).unwrap();
+        assert_eq!(turns("L270"), parse("R90"));
+        assert_eq!(turns("R270"), parse("L90"));
+        assert_eq!(turns("L90 L90"), parse("R180"));
+        assert_eq!(turns("R450 L-360"), parse("R90"));
+        assert_eq!(turns("R90 R90 R90 R90"), vec![]);
+        assert_eq!(
+            simplify(&parse("F1 R45"), ShipModel::Part1),
+            Err(NavigationError::UnsupportedTurn(Direction::Right(45)))
+        );
+    }
+
+    #[test]
+    fn test_long_program() {
+        // A jumble of everything, generated with a tiny linear congruential generator
+        let mut seed: u64 = 12345;
+        let program: Vec<Direction> = (0..500)
+            .map(|_| {
+                seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+                let n = (seed >> 33) as i64 % 20;
+                match (seed >> 60) % 7 {
+                    0 => Direction::North(n),
+                    1 => Direction::South(n),
+                    2 => Direction::East(n),
+                    3 => Direction::West(n),
+                    4 => Direction::Left(90 * (n % 5)),
+                    5 => Direction::Right(90 * (n % 5)),
+                    _ => Direction::Forward(n),
+                }
+            })
+            .collect();
+        for &model in &[ShipModel::Part1, ShipModel::Part2] {
+            // simplify replays both programs and fails if they end up in different places
+            let simplified = simplify(&program, model).unwrap();
+            assert!(simplified.len() < program.len());
+            assert_eq!(simplify(&simplified, model).unwrap(), simplified);
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! Records where a ship goes, and draws the route so we can look at it
+
+use std::fmt::Write;
+
+use super::{BoundingBox, Direction, Metric, NavigationError, Navigator, Position, RouteStats};
+
+/// Every position a ship (and its waypoint, if it has one) visited, starting where it began
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Track {
+    /// Where the ship was at the start, and after each instruction
+    pub ship: Vec<Position>,
+    /// Where the waypoint was at the start, and after each instruction (in the same coordinates as
+    /// the ship, not relative to it). None for ships without a waypoint
+    pub waypoint: Option<Vec<Position>>,
+}
+
+impl Track {
+    /// Runs every instruction on the ship, recording everywhere it goes
+    pub fn record<N: Navigator>(
+        ship: &mut N,
+        directions: &[Direction],
+    ) -> Result<Track, NavigationError> {
+        let mut track = Track {
+            ship: vec![ship.position()],
+            waypoint: ship.waypoint().map(|waypoint| vec![waypoint]),
+        };
+        for direction in directions {
+            ship.process_instruction(direction)?;
+            track.ship.push(ship.position());
+            if let (Some(waypoints), Some(waypoint)) = (track.waypoint.as_mut(), ship.waypoint()) {
+                waypoints.push(waypoint);
+            }
+        }
+        Ok(track)
+    }
+
+    /// Every point on the track, ship and waypoint
+    fn points(&self) -> impl Iterator<Item = &Position> {
+        self.ship.iter().chain(self.waypoint.iter().flatten())
+    }
+
+    /// Stats for the ship's route (ignoring the waypoint), measuring distance with `metric`.
+    /// None if nothing was recorded
+    pub fn stats(&self, metric: Metric) -> Option<RouteStats> {
+        RouteStats::new(&self.ship, metric)
+    }
+
+    /// The track as an SVG image: the ship's route in black (waypoint dashed in blue), with a green
+    /// dot where it started and a red dot where it finished
+    pub fn to_svg(&self) -> String {
+        let bounds = BoundingBox::around(self.points()).unwrap_or(BoundingBox {
+            min: Position::default(),
+            max: Position::default(),
+        });
+        let size = bounds.width().max(bounds.height()).max(1);
+        // Leave some room around the edge for the markers
+        let margin = size / 20 + 1;
+        let radius = size / 100 + 1;
+        let mut out = String::new();
+        writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="800" viewBox="{} {} {} {}">"#,
+            bounds.min.x - margin,
+            bounds.min.y - margin,
+            bounds.width() + 2 * margin,
+            bounds.height() + 2 * margin
+        )
+        .unwrap();
+        if let Some(waypoint) = &self.waypoint {
+            writeln!(
+                out,
+                r#"  <polyline points="{}" fill="none" stroke="steelblue" stroke-width="1" stroke-dasharray="4 4" vector-effect="non-scaling-stroke"/>"#,
+                svg_points(waypoint)
+            )
+            .unwrap();
+        }
+        writeln!(
+            out,
+            r#"  <polyline points="{}" fill="none" stroke="black" stroke-width="2" vector-effect="non-scaling-stroke"/>"#,
+            svg_points(&self.ship)
+        )
+        .unwrap();
+        let markers = [(self.ship.first(), "green"), (self.ship.last(), "red")];
+        for (point, colour) in markers.iter() {
+            if let Some(Position { x, y }) = point {
+                writeln!(
+                    out,
+                    r#"  <circle cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+                    x, y, radius, colour
+                )
+                .unwrap();
+            }
+        }
+        out.push_str("</svg>\n");
+        out
+    }
+
+    /// The track as a GeoJSON FeatureCollection.
+    ///
+    /// The coordinates are local [east, north] distances from where the ship started, not
+    /// longitude and latitude, so most map tools will need to be told not to reproject them
+    pub fn to_geojson(&self) -> String {
+        let mut features = vec![line_feature("ship", &self.ship)];
+        if let Some(waypoint) = &self.waypoint {
+            features.push(line_feature("waypoint", waypoint));
+        }
+        if let (Some(&start), Some(&end)) = (self.ship.first(), self.ship.last()) {
+            features.push(point_feature("start", start));
+            features.push(point_feature("end", end));
+        }
+        format!(
+            r#"{{"type":"FeatureCollection","features":[{}]}}"#,
+            features.join(",")
+        )
+    }
+}
+
+/// Points in SVG polyline format: "x,y x,y .."
+fn svg_points(points: &[Position]) -> String {
+    points
+        .iter()
+        .map(|Position { x, y }| format!("{},{}", x, y))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// A GeoJSON position. Our y goes south, but GeoJSON's goes north
+fn geojson_position(position: Position) -> String {
+    format!("[{},{}]", position.x, -position.y)
+}
+
+fn line_feature(name: &str, points: &[Position]) -> String {
+    let coordinates: Vec<String> = points.iter().cloned().map(geojson_position).collect();
+    format!(
+        r#"{{"type":"Feature","properties":{{"name":"{}"}},"geometry":{{"type":"LineString","coordinates":[{}]}}}}"#,
+        name,
+        coordinates.join(",")
+    )
+}
+
+fn point_feature(name: &str, point: Position) -> String {
+    format!(
+        r#"{{"type":"Feature","properties":{{"name":"{}"}},"geometry":{{"type":"Point","coordinates":{}}}}}"#,
+        name,
+        geojson_position(point)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Track;
+    use crate::model::fixtures::{directions, positions};
+    use crate::{part1, part2, Metric, Position};
+
+    #[test]
+    fn test_record() {
+        let track = Track::record(&mut part1::State::default(), &directions()).unwrap();
+        let expected = Track {
+            ship: positions(&[(0, 0), (10, 0), (10, -3), (17, -3), (17, -3), (17, 8)]),
+            waypoint: None,
+        };
+        assert_eq!(track, expected);
+        let track = Track::record(&mut part2::State::default(), &directions()).unwrap();
+        let expected = Track {
+            ship: positions(&[
+                (0, 0),
+                (100, -10),
+                (100, -10),
+                (170, -38),
+                (170, -38),
+                (214, 72),
+            ]),
+            waypoint: Some(positions(&[
+                (10, -1),
+                (110, -11),
+                (110, -14),
+                (180, -42),
+                (174, -28),
+                (218, 82),
+            ])),
+        };
+        assert_eq!(track, expected);
+    }
+
+    #[test]
+    fn test_stats() {
+        let track = Track::record(&mut part2::State::default(), &directions()).unwrap();
+        let stats = track.stats(Metric::Manhattan).unwrap();
+        assert_eq!(stats.furthest, Position::new(214, 72));
+        assert_eq!(stats.furthest_distance, 286.0);
+        assert_eq!(stats.bounding_box.min, Position::new(0, -38));
+        assert_eq!(stats.bounding_box.max, Position::new(214, 72));
+        assert_eq!(stats.travelled, 110.0 + 98.0 + 154.0);
+    }
+
+    #[test]
+    fn test_svg() {
+        let track = Track::record(&mut part1::State::default(), &directions()).unwrap();
+        let svg = track.to_svg();
+        assert!(svg.starts_with(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="800" viewBox="-1 -4 19 13">"#
+        ));
+        assert!(svg.contains(r#"<polyline points="0,0 10,0 10,-3 17,-3 17,-3 17,8""#));
+        assert!(svg.contains(r#"<circle cx="0" cy="0" r="1" fill="green"/>"#));
+        assert!(svg.contains(r#"<circle cx="17" cy="8" r="1" fill="red"/>"#));
+        assert!(!svg.contains("steelblue"));
+        let track = Track::record(&mut part2::State::default(), &directions()).unwrap();
+        assert!(track
+            .to_svg()
+            .contains(r#"<polyline points="10,-1 110,-11"#));
+    }
+
+    #[test]
+    fn test_geojson() {
+        let track = Track {
+            ship: positions(&[(0, 0), (3, -4)]),
+            waypoint: None,
+        };
+        let expected = concat!(
+            r#"{"type":"FeatureCollection","features":["#,
+            r#"{"type":"Feature","properties":{"name":"ship"},"geometry":{"type":"LineString","coordinates":[[0,0],[3,4]]}},"#,
+            r#"{"type":"Feature","properties":{"name":"start"},"geometry":{"type":"Point","coordinates":[0,0]}},"#,
+            r#"{"type":"Feature","properties":{"name":"end"},"geometry":{"type":"Point","coordinates":[3,4]}}"#,
+            r#"]}"#
+        );
+        assert_eq!(track.to_geojson(), expected);
+    }
+}
@@ -0,0 +1,457 @@
+//! A little language for describing departure patterns, and a solver that finds when they happen
+//!
+//! Part 2 only asks for "the bus at index i leaves at t + i". Here every bus gets an offset from
+//! the timestamp `t`, which can be pinned down to a range, or tied to another bus's offset:
+//!
+//! ```text
+//! bus 7 at 0
+//! bus 13 at 1 to 3
+//! bus 7 before bus 59 by 4
+//! ```
+//!
+//! Once every bus has an offset, the pattern is just part 2 again, so the solver tries each
+//! combination of offsets that fits and keeps the earliest timestamp the CRT gives it.
+
+use std::{collections::HashMap, error::Error, fmt, ops::RangeInclusive, str::FromStr};
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::crt::{self, Congruence, CrtError};
+
+/// One rule the buses have to follow. Offsets and gaps are in minutes and include both ends
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Constraint {
+    /// `bus` leaves `offset` minutes after the timestamp (negative means before it)
+    Offset {
+        bus: usize,
+        offset: RangeInclusive<i64>,
+    },
+    /// `first` leaves `minutes` before `second`
+    Before {
+        first: usize,
+        second: usize,
+        minutes: RangeInclusive<i64>,
+    },
+}
+
+/// Parses "3" or "1 to 5"
+fn parse_range(words: &[&str]) -> Result<RangeInclusive<i64>> {
+    match words {
+        [n] => {
+            let n = n.parse()?;
+            Ok(n..=n)
+        }
+        [start, "to", end] => Ok(start.parse()?..=end.parse()?),
+        _ => bail!("Expected a number, or a range like '1 to 5'"),
+    }
+}
+
+impl FromStr for Constraint {
+    type Err = anyhow::Error;
+
+    /// Parses "bus 7 at 0", "bus 13 at 1 to 3", "bus 7 before bus 59 by 4" or
+    /// "bus 59 after bus 7 by 2 to 4"
+    fn from_str(s: &str) -> Result<Constraint> {
+        let words: Vec<&str> = s.split_whitespace().collect();
+        match words.as_slice() {
+            ["bus", bus, "at", offset @ ..] => Ok(Constraint::Offset {
+                bus: bus.parse()?,
+                offset: parse_range(offset)?,
+            }),
+            ["bus", a, "before", "bus", b, "by", minutes @ ..] => Ok(Constraint::Before {
+                first: a.parse()?,
+                second: b.parse()?,
+                minutes: parse_range(minutes)?,
+            }),
+            ["bus", a, "after", "bus", b, "by", minutes @ ..] => Ok(Constraint::Before {
+                first: b.parse()?,
+                second: a.parse()?,
+                minutes: parse_range(minutes)?,
+            }),
+            _ => Err(anyhow!("Couldn't understand constraint: '{}'", s)),
+        }
+    }
+}
+
+/// Parses one constraint per line, skipping blank lines
+pub fn parse(input: &str) -> Result<Vec<Constraint>> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.parse())
+        .collect()
+}
+
+/// The part 2 pattern as constraints: the bus at index i leaves at t + i
+pub fn from_bus_ids(bus_ids: &[Option<usize>]) -> Vec<Constraint> {
+    bus_ids
+        .iter()
+        .enumerate()
+        .flat_map(|(index, bus)| {
+            bus.map(|bus| Constraint::Offset {
+                bus,
+                offset: index as i64..=index as i64,
+            })
+        })
+        .collect()
+}
+
+/// Why the constraints couldn't be solved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConstraintError {
+    /// Bus 0 never leaves, so it can't be in a pattern
+    ZeroBus,
+    /// The bus isn't tied to the timestamp, not even through other buses
+    Unanchored(usize),
+    /// No timestamp fits every constraint
+    NoSolution,
+    /// The answer doesn't fit in a u128
+    Overflow,
+}
+
+impl fmt::Display for ConstraintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstraintError::ZeroBus => write!(f, "Bus 0 never leaves the station"),
+            ConstraintError::Unanchored(bus) => write!(
+                f,
+                "Bus {} needs an offset, or a gap to a bus that has one",
+                bus
+            ),
+            ConstraintError::NoSolution => write!(f, "No timestamp fits every constraint"),
+            ConstraintError::Overflow => write!(f, "The answer is too big for a u128"),
+        }
+    }
+}
+
+impl Error for ConstraintError {}
+
+/// The earliest timestamp that fits, and when each bus leaves relative to it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    pub timestamp: u128,
+    /// (bus, offset), in the order the buses were first mentioned
+    pub offsets: Vec<(usize, i64)>,
+}
+
+/// Everything the search needs to know about one bus
+#[derive(Debug)]
+struct Slot {
+    bus: usize,
+    /// The offsets its own constraints allow, as (start, end)
+    range: Option<(i64, i64)>,
+    /// (earlier slot, range of this offset minus that one)
+    gaps: Vec<(usize, (i64, i64))>,
+    /// True if no later slot depends on this one's offset, so only its offset mod `bus` matters
+    last_word: bool,
+}
+
+/// Intersects two (start, end) ranges
+fn intersect(a: (i64, i64), b: (i64, i64)) -> (i64, i64) {
+    (a.0.max(b.0), a.1.min(b.1))
+}
+
+/// Adds `bus` to the list of buses if it's new
+fn mention(bus: usize, buses: &mut Vec<usize>) -> Result<(), ConstraintError> {
+    if bus == 0 {
+        return Err(ConstraintError::ZeroBus);
+    }
+    if !buses.contains(&bus) {
+        buses.push(bus);
+    }
+    Ok(())
+}
+
+/// Puts the buses in an order where each one either has its own offset or a gap to an earlier one
+fn slots(constraints: &[Constraint]) -> Result<Vec<Slot>, ConstraintError> {
+    let mut buses: Vec<usize> = Vec::new();
+    let mut ranges: HashMap<usize, (i64, i64)> = HashMap::new();
+    let mut neighbours: HashMap<usize, Vec<usize>> = HashMap::new();
+    for constraint in constraints {
+        match constraint {
+            Constraint::Offset { bus, offset } => {
+                mention(*bus, &mut buses)?;
+                let range = (*offset.start(), *offset.end());
+                let range = ranges.get(bus).map_or(range, |&old| intersect(old, range));
+                ranges.insert(*bus, range);
+            }
+            Constraint::Before {
+                first,
+                second,
+                minutes,
+            } => {
+                mention(*first, &mut buses)?;
+                mention(*second, &mut buses)?;
+                // A bus is always 0 minutes before itself
+                if first == second && !minutes.contains(&0) {
+                    return Err(ConstraintError::NoSolution);
+                }
+                neighbours.entry(*first).or_default().push(*second);
+                neighbours.entry(*second).or_default().push(*first);
+            }
+        }
+    }
+    // Start from the buses with offsets and work outwards along the gaps
+    let mut order: Vec<usize> = Vec::with_capacity(buses.len());
+    for &anchor in buses.iter().filter(|bus| ranges.contains_key(bus)) {
+        let mut next = order.len();
+        if !order.contains(&anchor) {
+            order.push(anchor);
+        }
+        while next < order.len() {
+            for &bus in neighbours.get(&order[next]).into_iter().flatten() {
+                if !order.contains(&bus) {
+                    order.push(bus);
+                }
+            }
+            next += 1;
+        }
+    }
+    if let Some(&bus) = buses.iter().find(|bus| !order.contains(bus)) {
+        return Err(ConstraintError::Unanchored(bus));
+    }
+    let mut slots: Vec<Slot> = order
+        .iter()
+        .map(|&bus| Slot {
+            bus,
+            range: ranges.get(&bus).cloned(),
+            gaps: Vec::new(),
+            last_word: true,
+        })
+        .collect();
+    let index = |bus: &usize| order.iter().position(|b| b == bus).unwrap();
+    for constraint in constraints {
+        if let Constraint::Before {
+            first,
+            second,
+            minutes,
+        } = constraint
+        {
+            let (first, second) = (index(first), index(second));
+            let (start, end) = (*minutes.start(), *minutes.end());
+            // second - first is in the range, so first - second is in the negated range
+            if first < second {
+                slots[second].gaps.push((first, (start, end)));
+                slots[first].last_word = false;
+            } else if second < first {
+                slots[first]
+                    .gaps
+                    .push((second, (end.saturating_neg(), start.saturating_neg())));
+                slots[second].last_word = false;
+            }
+        }
+    }
+    Ok(slots)
+}
+
+/// Tries every offset for each slot in turn, keeping the earliest timestamp
+struct Search {
+    slots: Vec<Slot>,
+    offsets: Vec<i64>,
+    best: Option<Solution>,
+}
+
+impl Search {
+    fn run(&mut self, depth: usize, so_far: Congruence) -> Result<(), ConstraintError> {
+        if depth == self.slots.len() {
+            if self
+                .best
+                .as_ref()
+                .is_none_or(|best| so_far.remainder < best.timestamp)
+            {
+                self.best = Some(Solution {
+                    timestamp: so_far.remainder,
+                    offsets: self
+                        .slots
+                        .iter()
+                        .map(|slot| slot.bus)
+                        .zip(self.offsets.iter().cloned())
+                        .collect(),
+                });
+            }
+            return Ok(());
+        }
+        let slot = &self.slots[depth];
+        let mut range = slot.range.unwrap_or((i64::MIN, i64::MAX));
+        for &(earlier, (start, end)) in &slot.gaps {
+            let offset = self.offsets[earlier];
+            range = intersect(
+                range,
+                (offset.saturating_add(start), offset.saturating_add(end)),
+            );
+        }
+        let (start, end) = range;
+        if start > end {
+            return Ok(());
+        }
+        let bus = slot.bus;
+        // Offsets a whole bus apart give the same departures, so unless another bus depends on
+        // exactly which one we pick, there's no point trying more than `bus` of them
+        let count = if slot.last_word {
+            (end as i128 - start as i128 + 1).min(bus as i128)
+        } else {
+            end as i128 - start as i128 + 1
+        };
+        for offset in (start..=end).take(count as usize) {
+            // t + offset ≡ 0 (mod bus)
+            let congruence = Congruence::new(-(offset as i128), bus as u128)
+                .expect("Bus 0 was rejected already");
+            match crt::combine(so_far, congruence) {
+                Ok(combined) => {
+                    self.offsets[depth] = offset;
+                    self.run(depth + 1, combined)?;
+                }
+                Err(CrtError::NoSolution { .. }) => continue,
+                Err(CrtError::Overflow) => return Err(ConstraintError::Overflow),
+                Err(CrtError::ZeroModulus) => unreachable!("Bus 0 was rejected already"),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Finds the earliest timestamp (0 or later) where every constraint holds. Every bus needs an
+/// offset, or a chain of gaps leading to a bus with one.
+///
+/// This tries every combination of offsets that the ranges allow, so wide ranges on lots of buses
+/// can take a while. If it returns `NoSolution`, it really did try them all
+pub fn solve(constraints: &[Constraint]) -> Result<Solution, ConstraintError> {
+    let slots = slots(constraints)?;
+    let mut search = Search {
+        offsets: vec![0; slots.len()],
+        slots,
+        best: None,
+    };
+    search.run(
+        0,
+        Congruence {
+            remainder: 0,
+            modulus: 1,
+        },
+    )?;
+    search.best.ok_or(ConstraintError::NoSolution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_bus_ids, parse, solve, Constraint, ConstraintError, Solution};
+    use crate::part_2;
+
+    fn timestamp(input: &str) -> Result<u128, ConstraintError> {
+        solve(&parse(input).unwrap()).map(|solution| solution.timestamp)
+    }
+
+    #[test]
+    fn test_parse() {
+        let got = parse("bus 7 at 0\n\nbus 13 at -1 to 3\nbus 7 before bus 59 by 4\nbus 19 after bus 7 by 2 to 4").unwrap();
+        assert_eq!(
+            got,
+            vec![
+                Constraint::Offset {
+                    bus: 7,
+                    offset: 0..=0
+                },
+                Constraint::Offset {
+                    bus: 13,
+                    offset: -1..=3
+                },
+                Constraint::Before {
+                    first: 7,
+                    second: 59,
+                    minutes: 4..=4
+                },
+                Constraint::Before {
+                    first: 7,
+                    second: 19,
+                    minutes: 2..=4
+                },
+            ]
+        );
+        assert!(parse("bus 7 at 1 to").is_err());
+        assert!(parse("bus 7 leaves at 1").is_err());
+    }
+
+    #[test]
+    fn test_same_as_part_2() {
+        for input in &[
+            "17,x,13,19",
+            "7,13,x,x,59,x,31,19",
+            "67,7,59,61",
+            "67,x,7,59,61",
+            "67,7,x,59,61",
+            "1789,37,47,1889",
+        ] {
+            let bus_ids = part_2::parse(input).unwrap();
+            let got = solve(&from_bus_ids(&bus_ids)).map(|solution| solution.timestamp);
+            assert_eq!(got.ok(), part_2::calculate(&bus_ids).ok());
+        }
+    }
+
+    #[test]
+    fn test_before() {
+        // Same as "7,13"
+        assert_eq!(timestamp("bus 7 at 0\nbus 7 before bus 13 by 1"), Ok(77));
+        assert_eq!(timestamp("bus 13 after bus 7 by 1\nbus 7 at 0"), Ok(77));
+        // Chains of gaps work too: "7,13,x,x,59"
+        let part_2 = part_2::calculate(&part_2::parse("7,13,x,x,59").unwrap()).unwrap();
+        assert_eq!(
+            timestamp("bus 7 at 0\nbus 7 before bus 13 by 1\nbus 13 before bus 59 by 3"),
+            Ok(part_2)
+        );
+        // Bus 13 leaving 1 minute before bus 7 leaves at t
+        assert_eq!(timestamp("bus 7 at 0\nbus 13 before bus 7 by 1"), Ok(14));
+    }
+
+    #[test]
+    fn test_ranges() {
+        let got = solve(&parse("bus 7 at 0\nbus 13 at 1 to 3").unwrap());
+        assert_eq!(
+            got,
+            Ok(Solution {
+                timestamp: 49,
+                offsets: vec![(7, 0), (13, 3)]
+            })
+        );
+        // Negative offsets leave before the timestamp
+        assert_eq!(timestamp("bus 13 at 0\nbus 7 at -1"), Ok(78));
+        // A range of gaps
+        assert_eq!(
+            timestamp("bus 7 at 0\nbus 7 before bus 13 by 2 to 4"),
+            Ok(35)
+        );
+        // Two offsets for one bus have to agree
+        assert_eq!(timestamp("bus 7 at 0 to 5\nbus 7 at 3 to 9"), Ok(2));
+    }
+
+    #[test]
+    fn test_no_solution() {
+        // Bus 4 always leaves at an even time, and bus 6 would need an odd one
+        assert_eq!(
+            timestamp("bus 4 at 0\nbus 6 at 1"),
+            Err(ConstraintError::NoSolution)
+        );
+        assert_eq!(
+            timestamp("bus 4 at 0\nbus 4 before bus 6 by 1 to 1"),
+            Err(ConstraintError::NoSolution)
+        );
+        // But an even gap is fine
+        assert_eq!(timestamp("bus 4 at 0\nbus 6 at 1 to 2"), Ok(4));
+        assert_eq!(
+            timestamp("bus 7 at 0 to 2\nbus 7 at 4 to 5"),
+            Err(ConstraintError::NoSolution)
+        );
+        assert_eq!(
+            timestamp("bus 7 at 0\nbus 7 before bus 7 by 3"),
+            Err(ConstraintError::NoSolution)
+        );
+    }
+
+    #[test]
+    fn test_bad_buses() {
+        assert_eq!(
+            timestamp("bus 7 before bus 13 by 1"),
+            Err(ConstraintError::Unanchored(7))
+        );
+        assert_eq!(timestamp("bus 0 at 1"), Err(ConstraintError::ZeroBus));
+    }
+}
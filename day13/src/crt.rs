@@ -0,0 +1,243 @@
+//! The Chinese Remainder Theorem, for moduli that don't have to be coprime
+//!
+//! Each bus gives us a congruence: `t + offset ≡ 0 (mod bus_id)`. We merge them two at a time using
+//! the extended Euclidean algorithm. If two moduli share a factor, their remainders have to agree
+//! on it, otherwise there's no answer at all.
+
+use std::{error::Error, fmt};
+
+/// `x ≡ remainder (mod modulus)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Congruence {
+    /// Always less than `modulus`
+    pub remainder: u128,
+    pub modulus: u128,
+}
+
+impl Congruence {
+    /// Makes a congruence, reducing `remainder` so it's less than `modulus`.
+    /// Returns None if `modulus` is 0
+    pub fn new(remainder: i128, modulus: u128) -> Option<Congruence> {
+        if modulus == 0 {
+            return None;
+        }
+        let remainder = if remainder >= 0 {
+            remainder as u128 % modulus
+        } else {
+            // -remainder as u128 is fine even for i128::MIN
+            let below = remainder.unsigned_abs() % modulus;
+            (modulus - below) % modulus
+        };
+        Some(Congruence { remainder, modulus })
+    }
+
+    /// Returns true if x satisfies this congruence
+    pub fn contains(&self, x: u128) -> bool {
+        x % self.modulus == self.remainder
+    }
+}
+
+impl fmt::Display for Congruence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "x ≡ {} (mod {})", self.remainder, self.modulus)
+    }
+}
+
+/// Why a system of congruences couldn't be solved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CrtError {
+    /// Two congruences contradict each other, so nothing satisfies both
+    NoSolution { a: Congruence, b: Congruence },
+    /// The combined modulus doesn't fit in a u128
+    Overflow,
+    /// A congruence mod 0, which doesn't mean anything (like bus 0, which never leaves)
+    ZeroModulus,
+}
+
+impl fmt::Display for CrtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CrtError::NoSolution { a, b } => {
+                write!(f, "No number satisfies both {} and {}", a, b)
+            }
+            CrtError::Overflow => write!(f, "The combined modulus is too big for a u128"),
+            CrtError::ZeroModulus => write!(f, "Can't solve a congruence mod 0"),
+        }
+    }
+}
+
+impl Error for CrtError {}
+
+/// Returns (gcd, x) where a * x ≡ gcd (mod b)
+fn extended_gcd(a: u128, b: u128) -> (u128, i128) {
+    // Invariants: old_r ≡ a * old_s and r ≡ a * s (mod b)
+    let (mut old_r, mut r) = (a as i128, b as i128);
+    let (mut old_s, mut s) = (1i128, 0i128);
+    while r != 0 {
+        let quotient = old_r / r;
+        let next_r = old_r - quotient * r;
+        old_r = r;
+        r = next_r;
+        let next_s = old_s - quotient * s;
+        old_s = s;
+        s = next_s;
+    }
+    (old_r as u128, old_s)
+}
+
+/// (a * b) % modulus, without overflowing
+fn mul_mod(a: u128, b: u128, modulus: u128) -> u128 {
+    let (a, mut b) = (a % modulus, b % modulus);
+    if let Some(product) = a.checked_mul(b) {
+        return product % modulus;
+    }
+    // Double and add, so nothing gets bigger than 2 * modulus
+    let mut result = 0;
+    let mut a = a;
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod(result, a, modulus);
+        }
+        a = add_mod(a, a, modulus);
+        b >>= 1;
+    }
+    result
+}
+
+/// (a + b) % modulus, for a and b already less than modulus
+fn add_mod(a: u128, b: u128, modulus: u128) -> u128 {
+    if a >= modulus - b {
+        a - (modulus - b)
+    } else {
+        a + b
+    }
+}
+
+/// Merges two congruences into one that holds exactly when both of them do
+pub fn combine(a: Congruence, b: Congruence) -> Result<Congruence, CrtError> {
+    // The extended gcd works in i128, so the moduli have to fit in one
+    if a.modulus > i128::MAX as u128 || b.modulus > i128::MAX as u128 {
+        return Err(CrtError::Overflow);
+    }
+    let (gcd, inverse) = extended_gcd(a.modulus, b.modulus);
+    // x = a.remainder + a.modulus * k, and we need that ≡ b.remainder (mod b.modulus)
+    let difference = if b.remainder >= a.remainder {
+        (b.remainder - a.remainder) % b.modulus
+    } else {
+        (b.modulus - (a.remainder - b.remainder) % b.modulus) % b.modulus
+    };
+    if difference % gcd != 0 {
+        return Err(CrtError::NoSolution { a, b });
+    }
+    let step = b.modulus / gcd;
+    let modulus = (a.modulus / gcd)
+        .checked_mul(b.modulus)
+        .ok_or(CrtError::Overflow)?;
+    let inverse = Congruence::new(inverse, step)
+        .expect("step is never 0")
+        .remainder;
+    let k = mul_mod(difference / gcd, inverse, step);
+    // a.remainder + a.modulus * k < a.modulus * step = modulus, so this never overflows
+    let remainder = a.remainder + a.modulus * k;
+    Ok(Congruence { remainder, modulus })
+}
+
+/// Finds every x that satisfies all the congruences, as a single congruence.
+/// The smallest answer is its `remainder`. No congruences at all means any number works
+pub fn solve<I>(congruences: I) -> Result<Congruence, CrtError>
+where
+    I: IntoIterator<Item = Congruence>,
+{
+    congruences.into_iter().try_fold(
+        Congruence {
+            remainder: 0,
+            modulus: 1,
+        },
+        combine,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mul_mod, solve, Congruence, CrtError};
+
+    fn congruence(remainder: i128, modulus: u128) -> Congruence {
+        Congruence::new(remainder, modulus).unwrap()
+    }
+
+    #[test]
+    fn test_new() {
+        assert_eq!(
+            congruence(-2, 7),
+            Congruence {
+                remainder: 5,
+                modulus: 7
+            }
+        );
+        assert_eq!(congruence(-14, 7).remainder, 0);
+        assert_eq!(congruence(23, 7).remainder, 2);
+        assert_eq!(Congruence::new(1, 0), None);
+    }
+
+    #[test]
+    fn test_coprime() {
+        // The classic: 2 mod 3, 3 mod 5, 2 mod 7
+        let got = solve(vec![congruence(2, 3), congruence(3, 5), congruence(2, 7)]);
+        assert_eq!(got, Ok(congruence(23, 105)));
+    }
+
+    #[test]
+    fn test_not_coprime() {
+        let got = solve(vec![congruence(0, 4), congruence(4, 6)]);
+        assert_eq!(got, Ok(congruence(4, 12)));
+        let got = solve(vec![
+            congruence(3, 10),
+            congruence(13, 15),
+            congruence(1, 6),
+        ]);
+        assert_eq!(got, Ok(congruence(13, 30)));
+    }
+
+    #[test]
+    fn test_no_solution() {
+        // Even numbers are never odd
+        let got = solve(vec![congruence(0, 4), congruence(5, 6)]);
+        assert_eq!(
+            got,
+            Err(CrtError::NoSolution {
+                a: congruence(0, 4),
+                b: congruence(5, 6)
+            })
+        );
+    }
+
+    #[test]
+    fn test_big() {
+        // Two primes near 2^61: the answer needs more than 64 bits
+        let p = (1u128 << 61) - 1;
+        let q = (1u128 << 61) + 15;
+        let got = solve(vec![congruence(1, p), congruence(2, q)]).unwrap();
+        assert_eq!(got.modulus, p * q);
+        assert!(got.contains(got.remainder));
+        assert_eq!(got.remainder % p, 1);
+        assert_eq!(got.remainder % q, 2);
+        // The combined modulus won't fit
+        let r = (1u128 << 64) + 13;
+        assert_eq!(
+            solve(vec![congruence(0, p), congruence(0, q), congruence(0, r)]),
+            Err(CrtError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_mul_mod() {
+        assert_eq!(mul_mod(6, 7, 10), 2);
+        // 2^200 overflows, but 2^127 ≡ 1 (mod 2^127 - 1)
+        assert_eq!(mul_mod(1 << 100, 1 << 100, (1 << 127) - 1), 1 << 73);
+    }
+
+    #[test]
+    fn test_nothing() {
+        assert_eq!(solve(vec![]), Ok(congruence(0, 1)));
+    }
+}
@@ -1 +1,5 @@
+pub mod constraints;
+pub mod crt;
+pub mod part_1;
 pub mod part_2;
+pub mod timetable;
@@ -1,32 +1,56 @@
 use anyhow::{anyhow, Result};
-use day13::part_2;
-use std::fs::read_to_string;
-
-/// Parses the puzzle input and returns (earliest_you_can_leave, bus_ids)
-fn parse(input: &str) -> Result<(usize, Vec<usize>)> {
-    let lines: Vec<&str> = input.lines().collect();
-    assert_eq!(lines.len(), 2);
-    // The earliest I can leave
-    let earliest: usize = lines[0].parse()?;
-    // The IDs of the busses
-    let bus_ids: Vec<usize> = lines[1].split(',').flat_map(|id| id.parse().ok()).collect();
-    Ok((earliest, bus_ids))
-}
-
-//// Returns (bus_id, minutes you have to wait (after `earliest`))
-fn earliest_bus_id(earliest: usize, bus_ids: &[usize]) -> Option<(usize, usize)> {
-    bus_ids
-        .iter()
-        .map(|bus_id| (bus_id, bus_id - (earliest % bus_id)))
-        .min_by_key(|(_bus_id, to_wait)| *to_wait)
-        .map(|(&bus_id, to_wait)| (bus_id, to_wait))
-}
+use day13::{constraints, part_1, part_2, timetable::Timetable};
+use std::{env::args, fs::read_to_string};
 
 fn main() -> Result<()> {
     let input = read_to_string("input.txt")?;
-    let (earliest, bus_ids) = parse(&input)?;
-    let (bus_id, to_wait) =
-        earliest_bus_id(earliest, &bus_ids).ok_or_else(|| anyhow!("Probably there 0 bus_ids"))?;
+    // `day13 timetable [count]` shows the buses leaving around when we get to the station
+    if args().nth(1).as_deref() == Some("timetable") {
+        let count = args().nth(2).map(|n| n.parse()).transpose()?.unwrap_or(10);
+        let (earliest, _) = part_1::parse(&input)?;
+        let line = input.lines().nth(1).expect("More input lines");
+        let timetable = Timetable::parse(line)?;
+        for departure in timetable.next_departures(earliest, count) {
+            println!(
+                "Bus {} leaves at {} (wait {} minutes)",
+                departure.bus_id,
+                departure.time,
+                departure.time - earliest
+            );
+        }
+        let last = timetable
+            .next_departures(earliest, 1)
+            .first()
+            .map_or(earliest, |departure| departure.time);
+        print!(
+            "{}",
+            timetable.render(earliest.saturating_sub(2)..=last + 2)
+        );
+        return Ok(());
+    }
+    // `day13 constraints <file>` finds the earliest time a pattern of departures happens
+    if args().nth(1).as_deref() == Some("constraints") {
+        let path = args()
+            .nth(2)
+            .ok_or_else(|| anyhow!("Which file are the constraints in?"))?;
+        let constraints = constraints::parse(&read_to_string(path)?)?;
+        let solution = constraints::solve(&constraints)?;
+        println!(
+            "Day13 - Constraints - earliest timestamp: {}",
+            solution.timestamp
+        );
+        for (bus, offset) in solution.offsets {
+            println!(
+                "Bus {} leaves at {}",
+                bus,
+                solution.timestamp as i128 + offset as i128
+            );
+        }
+        return Ok(());
+    }
+    let (earliest, bus_ids) = part_1::parse(&input)?;
+    let (bus_id, to_wait) = part_1::earliest_bus_id(earliest, &bus_ids)
+        .ok_or_else(|| anyhow!("Probably there 0 bus_ids"))?;
     let answer = to_wait * bus_id;
     println!(
         "Day13 - Part 1 - Bus ID: {} minutes to wait: {} answer: {}",
@@ -36,33 +60,7 @@ fn main() -> Result<()> {
     let input = read_to_string("input.txt")?;
     let line = input.lines().nth(1).expect("More input lines");
     let bus_ids = part_2::parse(line)?;
-    let answer = part_2::calculate(&bus_ids).expect("An answer");
+    let answer = part_2::calculate(&bus_ids)?;
     println!("Day13 - Part 2 - answer: {}", answer);
     Ok(())
 }
-
-#[cfg(test)]
-mod tests {
-    use anyhow::{anyhow, Result};
-
-    #[test]
-    fn test_parse() -> Result<()> {
-        let input = "939
-7,13,x,x,59,x,31,19";
-        let (earliest, bus_ids) = super::parse(input)?;
-        assert_eq!(earliest, 939);
-        assert_eq!(bus_ids, vec![7, 13, 59, 31, 19]);
-        Ok(())
-    }
-
-    #[test]
-    fn test_earliest_bus_id() -> Result<()> {
-        let earliest = 939;
-        let bus_ids = vec![7, 13, 59, 31, 19];
-        let (bus_id, to_wait) = super::earliest_bus_id(earliest, &bus_ids)
-            .ok_or_else(|| anyhow!("Probably there are no bus_ids"))?;
-        assert_eq!(bus_id, 59);
-        assert_eq!(to_wait, 5);
-        Ok(())
-    }
-}
@@ -0,0 +1,59 @@
+//! Part 1: catching the first bus to the airport
+
+use anyhow::Result;
+
+/// Parses the puzzle input and returns (earliest_you_can_leave, bus_ids)
+pub fn parse(input: &str) -> Result<(usize, Vec<usize>)> {
+    let lines: Vec<&str> = input.lines().collect();
+    assert_eq!(lines.len(), 2);
+    // The earliest I can leave
+    let earliest: usize = lines[0].parse()?;
+    // The IDs of the busses
+    let bus_ids: Vec<usize> = lines[1].split(',').flat_map(|id| id.parse().ok()).collect();
+    Ok((earliest, bus_ids))
+}
+
+/// Returns (bus_id, minutes you have to wait (after `earliest`))
+pub fn earliest_bus_id(earliest: usize, bus_ids: &[usize]) -> Option<(usize, usize)> {
+    bus_ids
+        .iter()
+        // A bus leaving right at `earliest` means no wait at all
+        .map(|bus_id| (bus_id, (bus_id - earliest % bus_id) % bus_id))
+        .min_by_key(|(_bus_id, to_wait)| *to_wait)
+        .map(|(&bus_id, to_wait)| (bus_id, to_wait))
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::{anyhow, Result};
+
+    #[test]
+    fn test_parse() -> Result<()> {
+        let input = "939
+7,13,x,x,59,x,31,19";
+        let (earliest, bus_ids) = super::parse(input)?;
+        assert_eq!(earliest, 939);
+        assert_eq!(bus_ids, vec![7, 13, 59, 31, 19]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_earliest_bus_id() -> Result<()> {
+        let earliest = 939;
+        let bus_ids = vec![7, 13, 59, 31, 19];
+        let (bus_id, to_wait) = super::earliest_bus_id(earliest, &bus_ids)
+            .ok_or_else(|| anyhow!("Probably there are no bus_ids"))?;
+        assert_eq!(bus_id, 59);
+        assert_eq!(to_wait, 5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bus_leaving_now() {
+        // 949 is a multiple of 13, so we don't have to wait
+        assert_eq!(super::earliest_bus_id(949, &[7, 13, 59]), Some((13, 0)));
+        let timetable = crate::timetable::Timetable::parse("7,13,59").unwrap();
+        let next = timetable.next_departures(949, 1)[0];
+        assert_eq!((next.bus_id, next.time - 949), (13, 0));
+    }
+}
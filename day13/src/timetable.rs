@@ -0,0 +1,196 @@
+//! Questions about when the buses leave, rather than puzzle answers
+//!
+//! Every bus leaves the station at timestamp 0, then again every `bus_id` minutes after that.
+
+use std::{cmp::Reverse, collections::BinaryHeap, fmt::Write, ops::RangeInclusive};
+
+use anyhow::Result;
+
+use crate::part_2;
+
+/// One bus leaving the station
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Departure {
+    pub time: usize,
+    pub bus_id: usize,
+}
+
+/// The buses in the order they appear in the input, where None means `x`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timetable {
+    pub buses: Vec<Option<usize>>,
+}
+
+impl Timetable {
+    pub fn new(buses: Vec<Option<usize>>) -> Timetable {
+        Timetable { buses }
+    }
+
+    /// Parses a line of bus ids like "7,13,x,x,59"
+    pub fn parse(line: &str) -> Result<Timetable> {
+        Ok(Timetable::new(part_2::parse(line)?))
+    }
+
+    /// The buses that are actually running. A bus with id 0 would never go anywhere, so it's skipped
+    pub fn bus_ids(&self) -> impl Iterator<Item = usize> + '_ {
+        self.buses.iter().flatten().cloned().filter(|&id| id > 0)
+    }
+
+    /// When `bus_id` next leaves, at or after `time`
+    fn next_time(bus_id: usize, time: usize) -> usize {
+        time.div_ceil(bus_id) * bus_id
+    }
+
+    /// Every departure at or after `after`, in time order. Buses leaving at the same time come out
+    /// smallest id first. Never ends, unless there are no buses
+    pub fn departures(&self, after: usize) -> impl Iterator<Item = Departure> {
+        let mut queue: BinaryHeap<Reverse<Departure>> = self
+            .bus_ids()
+            .map(|bus_id| {
+                Reverse(Departure {
+                    time: Timetable::next_time(bus_id, after),
+                    bus_id,
+                })
+            })
+            .collect();
+        std::iter::from_fn(move || {
+            let Reverse(departure) = queue.pop()?;
+            queue.push(Reverse(Departure {
+                time: departure.time + departure.bus_id,
+                bus_id: departure.bus_id,
+            }));
+            Some(departure)
+        })
+    }
+
+    /// The next `n` departures at or after `after`
+    pub fn next_departures(&self, after: usize, n: usize) -> Vec<Departure> {
+        self.departures(after).take(n).collect()
+    }
+
+    /// Every departure in the window, in time order
+    pub fn leaving_within(&self, window: RangeInclusive<usize>) -> Vec<Departure> {
+        let end = *window.end();
+        self.departures(*window.start())
+            .take_while(|departure| departure.time <= end)
+            .collect()
+    }
+
+    /// A table like the one in the puzzle, with a row for every timestamp in `times`, a column for
+    /// every running bus, and a `D` wherever a bus departs
+    pub fn render(&self, times: RangeInclusive<usize>) -> String {
+        let bus_ids: Vec<usize> = self.bus_ids().collect();
+        let time_width = times.end().to_string().len().max(4) + 3;
+        let headers: Vec<String> = bus_ids.iter().map(|id| format!("bus {}", id)).collect();
+        let widths: Vec<usize> = headers
+            .iter()
+            .map(|header| (header.len() + 2).max(8))
+            .collect();
+        let mut out = String::new();
+        write!(out, "{:<width$}", "time", width = time_width).unwrap();
+        for (header, width) in headers.iter().zip(&widths) {
+            write!(out, "{:<width$}", header, width = width).unwrap();
+        }
+        finish_line(&mut out);
+        for time in times {
+            write!(out, "{:<width$}", time, width = time_width).unwrap();
+            for (&bus_id, width) in bus_ids.iter().zip(&widths) {
+                let cell = if time.is_multiple_of(bus_id) {
+                    'D'
+                } else {
+                    '.'
+                };
+                write!(out, "  {:<width$}", cell, width = width - 2).unwrap();
+            }
+            finish_line(&mut out);
+        }
+        out
+    }
+}
+
+/// Trims the padding off the end of the last line and starts a new one
+fn finish_line(out: &mut String) {
+    out.truncate(out.trim_end_matches(' ').len());
+    out.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Departure, Timetable};
+
+    fn example() -> Timetable {
+        Timetable::parse("7,13,x,x,59,x,31,19").unwrap()
+    }
+
+    fn departure(time: usize, bus_id: usize) -> Departure {
+        Departure { time, bus_id }
+    }
+
+    #[test]
+    fn test_next_departures() {
+        let got = example().next_departures(939, 4);
+        assert_eq!(
+            got,
+            vec![
+                departure(944, 59),
+                departure(945, 7),
+                departure(949, 13),
+                departure(950, 19)
+            ]
+        );
+        // A bus leaving right at the timestamp counts
+        assert_eq!(
+            example().next_departures(0, 2),
+            vec![departure(0, 7), departure(0, 13)]
+        );
+        assert_eq!(
+            Timetable::parse("x,x").unwrap().next_departures(10, 3),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_leaving_within() {
+        let got = example().leaving_within(929..=938);
+        assert_eq!(
+            got,
+            vec![
+                departure(930, 31),
+                departure(931, 7),
+                departure(931, 19),
+                departure(936, 13),
+                departure(938, 7)
+            ]
+        );
+        assert_eq!(example().leaving_within(939..=943), vec![]);
+    }
+
+    #[test]
+    fn test_render() {
+        let expected = "\
+time   bus 7   bus 13  bus 59  bus 31  bus 19
+929      .       .       .       .       .
+930      .       .       .       D       .
+931      D       .       .       .       D
+932      .       .       .       .       .
+933      .       .       .       .       .
+934      .       .       .       .       .
+935      .       .       .       .       .
+936      .       D       .       .       .
+937      .       .       .       .       .
+938      D       .       .       .       .
+939      .       .       .       .       .
+940      .       .       .       .       .
+941      .       .       .       .       .
+942      .       .       .       .       .
+943      .       .       .       .       .
+944      .       .       D       .       .
+945      D       .       .       .       .
+946      .       .       .       .       .
+947      .       .       .       .       .
+948      .       .       .       .       .
+949      .       D       .       .       .
+";
+        assert_eq!(example().render(929..=949), expected);
+    }
+}
@@ -1,17 +1,47 @@
-use std::fs::read_to_string;
+use std::{env::args, fs::read_to_string};
 
-use day14::model::{Part2Blocks, WriterBlocks};
+use anyhow::anyhow;
+use day14::model::{Decoder, Program, WordWidth, V1, V2};
+
+/// Runs one of the debugging commands with whichever decoder we were asked for
+fn report<D: Decoder>(command: &str, program: &Program, decoder: &mut D) -> anyhow::Result<()> {
+    match command {
+        "dump" => print!("{}", program.run(decoder)?.dump_hex(program.width())),
+        "trace" => print!("{}", program.trace(decoder)?),
+        _ => {
+            for overwrite in program.overwrites(decoder)? {
+                println!("{}", overwrite);
+            }
+        }
+    }
+    Ok(())
+}
 
 fn main() -> anyhow::Result<()> {
     let input = read_to_string("input.txt")?;
-    let blocks: WriterBlocks = input.parse()?;
-    let values = blocks.write();
-    let answer: usize = values.values().sum();
+    let program: Program = input.parse()?;
+    // `day14 dump <part> [width]` lists memory in hex after running the program,
+    // `day14 trace <part> [width]` shows what every write did, and
+    // `day14 overwrites <part> [width]` shows which lines wrote over earlier ones
+    if let Some(command) = args()
+        .nth(1)
+        .filter(|command| ["dump", "trace", "overwrites"].contains(&command.as_str()))
+    {
+        let width = match args().nth(3) {
+            Some(bits) => WordWidth::new(bits.parse()?)?,
+            None => WordWidth::default(),
+        };
+        let program = program.with_width(width);
+        return match args().nth(2).as_deref() {
+            Some("1") => report(&command, &program, &mut V1::default()),
+            Some("2") => report(&command, &program, &mut V2::default()),
+            _ => Err(anyhow!("Usage: day14 {} <1|2> [width]", command)),
+        };
+    }
+    let answer = program.run(&mut V1::default())?.sum();
     println!("Day 14 - Part 1 - answer: {}", answer);
-    // Part 2
-    let blocks: Part2Blocks = input.parse()?;
-    let values = blocks.write();
-    let answer: usize = values.values().sum();
+    // Part 2. Wild bits can write to a lot of addresses, so they're never listed one by one
+    let answer = program.run(&mut V2::default())?.sum();
     println!("Day 14 - Part 2 - answer: {}", answer);
     Ok(())
 }
@@ -4,17 +4,26 @@ pub use bitmask::{Bit, BitMask};
 mod instruction;
 pub use instruction::Instruction;
 
-mod writer_block;
-pub use writer_block::WriterBlock;
-
-mod writer_blocks;
-pub use writer_blocks::WriterBlocks;
-
 mod part2_mask;
 pub use part2_mask::{BitValue, Part2Mask};
 
-mod part2_block;
-pub use part2_block::Part2Block;
+mod floating_memory;
+pub use floating_memory::{AddressPattern, FloatingMemory};
+
+mod mask;
+pub use mask::Mask;
+
+mod decoder;
+pub use decoder::{Decoder, Write, V1, V2};
+
+mod program;
+pub use program::{Line, Program};
+
+mod error;
+pub use error::WidthError;
+
+mod word_width;
+pub use word_width::WordWidth;
 
-mod part2_blocks;
-pub use part2_blocks::Part2Blocks;
+mod trace;
+pub use trace::{Overwrite, Step, Trace};
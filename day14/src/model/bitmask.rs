@@ -5,38 +5,39 @@ mod parse;
 pub struct Bit {
     /// Its position / index in the bitmask
     /// 0 means the least significant bit
-    /// Max will be 36
+    /// Usually less than 36, but anything up to 63 fits in a usize
     position: usize,
     /// Whether this bit should be turned on
     value: bool,
 }
 
 impl Bit {
-    /// Only used so we can create some mockups for testing
-    /// To actuall generate one of these just parse the input
-    #[cfg(test)]
     pub fn new(position: usize, value: bool) -> Bit {
         Bit { position, value }
     }
     fn apply(self, input: &mut usize) {
+        // A usize doesn't have a bit past 63, so there's nothing to set or clear
+        if self.position >= usize::BITS as usize {
+            return;
+        }
+        let bit = 1 << self.position;
         if self.value {
             // Set the bit
-            *input |= 1 << self.position;
+            *input |= bit;
         } else {
             // Clear the bit
-            *input &= !(1 << self.position);
+            *input &= !bit;
         }
     }
 }
 
-#[derive(Default, Debug, PartialEq, Eq)]
+#[derive(Default, Debug, PartialEq, Eq, Clone)]
 pub struct BitMask {
     bits: Vec<Bit>,
 }
 
 impl BitMask {
-    /// Only used in testing to make mockups
-    #[cfg(test)]
+    /// Usually you'd parse a `Mask` and call `value_mask` on it instead
     pub fn new(bits: Vec<Bit>) -> BitMask {
         BitMask { bits }
     }
@@ -46,3 +47,17 @@ impl BitMask {
         input
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Bit, BitMask};
+
+    #[test]
+    fn test_apply_to() {
+        let mask = BitMask::new(vec![Bit::new(1, false), Bit::new(6, true)]);
+        assert_eq!(mask.apply_to(11), 73);
+        // Bits that don't exist in a usize are left alone rather than overflowing
+        let mask = BitMask::new(vec![Bit::new(64, true), Bit::new(100, false)]);
+        assert_eq!(mask.apply_to(11), 11);
+    }
+}
@@ -1,37 +1,17 @@
-use anyhow::{anyhow, Error};
+use anyhow::Error;
 use std::str::FromStr;
 
-use super::{Bit, BitMask};
+use crate::model::Mask;
+
+use super::BitMask;
 
 impl FromStr for BitMask {
     type Err = Error;
 
+    /// Parses a whole mask line, keeping only the bits decoder v1 uses
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // Example line
-        // mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X
-        let parts: Vec<&str> = s.split_ascii_whitespace().collect();
-        match parts.as_slice() {
-            ["mask", "=", mask] => {
-                let bits: Vec<Bit> = mask
-                    .chars()
-                    .rev()
-                    .enumerate()
-                    .flat_map(|(position, c)| match &c {
-                        '0' => Some(Bit {
-                            position,
-                            value: false,
-                        }),
-                        '1' => Some(Bit {
-                            position,
-                            value: true,
-                        }),
-                        _ => None,
-                    })
-                    .collect();
-                Ok(BitMask { bits })
-            }
-            _ => Err(anyhow!("Invalid mask line: {}", s)),
-        }
+        let mask: Mask = s.parse()?;
+        Ok(mask.value_mask())
     }
 }
 
@@ -0,0 +1,104 @@
+//! Decoder chips: they decide what a `mem[..] = ..` line actually writes, given the current mask
+//!
+//! Version 1 masks the value, version 2 masks the address. Anything else that can describe a write
+//! as a pattern of addresses and a value can be plugged into `Program::run` too.
+
+use super::{AddressPattern, BitMask, Instruction, Mask, Part2Mask};
+
+/// One write to memory, after decoding
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Write {
+    /// Every address that gets the value
+    pub addresses: AddressPattern,
+    pub value: usize,
+}
+
+/// A chip that turns `mem[..] = ..` lines into writes
+pub trait Decoder {
+    /// Called for every `mask = ..` line
+    fn set_mask(&mut self, mask: &Mask);
+    /// Works out what a `mem[..] = ..` line writes, using the last mask we were given
+    fn decode(&self, instruction: &Instruction) -> Write;
+}
+
+/// Decoder version 1: the mask overwrites bits of the value
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct V1 {
+    mask: BitMask,
+}
+
+impl Decoder for V1 {
+    fn set_mask(&mut self, mask: &Mask) {
+        self.mask = mask.value_mask();
+    }
+
+    fn decode(&self, instruction: &Instruction) -> Write {
+        Write {
+            addresses: AddressPattern::new(instruction.location, 0),
+            value: self.mask.apply_to(instruction.value),
+        }
+    }
+}
+
+/// Decoder version 2: the mask sets and floats bits of the address
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct V2 {
+    mask: Part2Mask,
+}
+
+impl Decoder for V2 {
+    fn set_mask(&mut self, mask: &Mask) {
+        self.mask = mask.address_mask();
+    }
+
+    fn decode(&self, instruction: &Instruction) -> Write {
+        Write {
+            addresses: self.mask.pattern(instruction.location),
+            value: instruction.value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Decoder, Write, V1, V2};
+    use crate::model::{AddressPattern, Instruction, Mask};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_v1() {
+        let mut decoder = V1::default();
+        let mask: Mask = "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X"
+            .parse()
+            .unwrap();
+        decoder.set_mask(&mask);
+        let write = decoder.decode(&Instruction {
+            location: 8,
+            value: 11,
+        });
+        assert_eq!(
+            write,
+            Write {
+                addresses: AddressPattern::new(8, 0),
+                value: 73
+            }
+        );
+    }
+
+    #[test]
+    fn test_v2() {
+        let mut decoder = V2::default();
+        let mask: Mask = "mask = 000000000000000000000000000000X1001X"
+            .parse()
+            .unwrap();
+        decoder.set_mask(&mask);
+        let write = decoder.decode(&Instruction {
+            location: 42,
+            value: 100,
+        });
+        assert_eq!(write.value, 100);
+        let mut addresses: Vec<usize> = write.addresses.addresses().collect();
+        addresses.sort();
+        assert_eq!(addresses, vec![26, 27, 58, 59]);
+    }
+}
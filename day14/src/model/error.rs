@@ -0,0 +1,56 @@
+//! Things that can go wrong when running a program on a machine with a fixed word size
+
+use std::{error::Error, fmt};
+
+/// Something in the program doesn't fit in the machine's words. Lines count from 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WidthError {
+    /// Words have to be between 1 and 64 bits (the size of a usize)
+    InvalidWidth(u32),
+    /// The mask has bits past the end of the word
+    MaskTooWide { line: usize, width: u32 },
+    /// The value being written (or what the decoder turned it into) doesn't fit
+    ValueTooWide {
+        line: usize,
+        value: usize,
+        width: u32,
+    },
+    /// The address being written to (or one the decoder turned it into) doesn't fit
+    AddressTooWide {
+        line: usize,
+        address: usize,
+        width: u32,
+    },
+}
+
+impl fmt::Display for WidthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WidthError::InvalidWidth(width) => write!(
+                f,
+                "Words can't be {} bits wide, they have to be between 1 and {}",
+                width,
+                usize::BITS
+            ),
+            WidthError::MaskTooWide { line, width } => {
+                write!(f, "Line {}: the mask is wider than {} bits", line, width)
+            }
+            WidthError::ValueTooWide { line, value, width } => write!(
+                f,
+                "Line {}: the value {} doesn't fit in {} bits",
+                line, value, width
+            ),
+            WidthError::AddressTooWide {
+                line,
+                address,
+                width,
+            } => write!(
+                f,
+                "Line {}: the address {} doesn't fit in {} bits",
+                line, address, width
+            ),
+        }
+    }
+}
+
+impl Error for WidthError {}
@@ -0,0 +1,312 @@
+//! Memory for decoder v2 that never lists the addresses a write touches.
+//!
+//! A mask with `k` wild bits writes to 2^k addresses, so instead of storing each one, we store the
+//! whole write as an `AddressPattern`. When a new write lands on top of older ones, we cut the
+//! overlap out of the old patterns, which leaves a handful of smaller patterns behind. Every
+//! address then belongs to at most one pattern, so the sum is just value * count for each one.
+
+use std::{collections::HashMap, fmt::Write};
+
+use super::WordWidth;
+
+/// A set of addresses: the bits in `floating` can be anything, and every other bit matches `value`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct AddressPattern {
+    /// The fixed bits. Floating bits are always 0 in here
+    value: usize,
+    /// A 1 for every wild bit
+    floating: usize,
+}
+
+impl AddressPattern {
+    pub fn new(value: usize, floating: usize) -> AddressPattern {
+        AddressPattern {
+            value: value & !floating,
+            floating,
+        }
+    }
+
+    /// How many addresses match
+    pub fn count(&self) -> u128 {
+        1 << self.floating.count_ones()
+    }
+
+    /// The biggest address that matches
+    pub fn highest(&self) -> usize {
+        self.value | self.floating
+    }
+
+    /// True if `address` matches the pattern
+    pub fn contains(&self, address: usize) -> bool {
+        address & !self.floating == self.value
+    }
+
+    /// True if at least one address matches both patterns
+    pub fn overlaps(&self, other: &AddressPattern) -> bool {
+        // Bits that are fixed in both have to agree
+        (self.value ^ other.value) & !(self.floating | other.floating) == 0
+    }
+
+    /// The addresses in both patterns, if there are any
+    pub fn intersection(&self, other: &AddressPattern) -> Option<AddressPattern> {
+        if self.overlaps(other) {
+            // Where either one is fixed, they agree (or one is floating), so `|` picks the fixed bit
+            Some(AddressPattern::new(
+                self.value | other.value,
+                self.floating & other.floating,
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// The pattern in binary, `width` bits long, with an `X` for every floating bit
+    pub fn to_binary(&self, width: WordWidth) -> String {
+        (0..width.bits())
+            .rev()
+            .map(|position| {
+                let bit = 1 << position;
+                if self.floating & bit != 0 {
+                    'X'
+                } else if self.value & bit != 0 {
+                    '1'
+                } else {
+                    '0'
+                }
+            })
+            .collect()
+    }
+
+    /// The addresses in `self` that aren't in `other`, as patterns that don't overlap each other
+    pub fn subtract(&self, other: &AddressPattern) -> Vec<AddressPattern> {
+        if !self.overlaps(other) {
+            return vec![*self];
+        }
+        let mut out = Vec::new();
+        let mut rest = *self;
+        // Bits that float here but are fixed in `other`. Pinning each one to the opposite of
+        // `other` splits off a piece that misses it, and the rest carries on with it matching
+        let mut to_split = self.floating & !other.floating;
+        while to_split != 0 {
+            let bit = to_split & to_split.wrapping_neg();
+            to_split &= !bit;
+            rest.floating &= !bit;
+            out.push(AddressPattern {
+                value: rest.value | (!other.value & bit),
+                floating: rest.floating,
+            });
+            rest.value |= other.value & bit;
+        }
+        // Whatever is left is entirely inside `other`
+        out
+    }
+
+    /// Every address that matches. There are `count()` of them, so only use this on small patterns
+    pub fn addresses(&self) -> impl Iterator<Item = usize> + '_ {
+        // Counting down through the subsets of `floating`, then stopping after 0
+        let mut subset = Some(self.floating);
+        std::iter::from_fn(move || {
+            let current = subset?;
+            subset = if current == 0 {
+                None
+            } else {
+                Some((current - 1) & self.floating)
+            };
+            Some(self.value | current)
+        })
+    }
+}
+
+/// Memory where each write covers a whole `AddressPattern`
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct FloatingMemory {
+    /// Patterns that don't overlap each other, and the value stored at all their addresses
+    writes: Vec<(AddressPattern, usize)>,
+}
+
+impl FloatingMemory {
+    pub fn new() -> FloatingMemory {
+        FloatingMemory::default()
+    }
+
+    /// Writes `value` to every address in `pattern`, replacing anything that was already there
+    pub fn write(&mut self, pattern: AddressPattern, value: usize) {
+        self.writes = self
+            .writes
+            .iter()
+            .flat_map(|(old, old_value)| {
+                old.subtract(&pattern)
+                    .into_iter()
+                    .map(move |piece| (piece, *old_value))
+            })
+            .collect();
+        self.writes.push((pattern, value));
+    }
+
+    /// The value at `address`, if anything was written there
+    pub fn get(&self, address: usize) -> Option<usize> {
+        self.writes
+            .iter()
+            .find(|(pattern, _)| pattern.contains(address))
+            .map(|(_, value)| *value)
+    }
+
+    /// The parts of memory that `pattern` covers, and what's stored there
+    pub fn overlapping(&self, pattern: &AddressPattern) -> Vec<(AddressPattern, usize)> {
+        self.writes
+            .iter()
+            .flat_map(|(old, value)| old.intersection(pattern).map(|piece| (piece, *value)))
+            .collect()
+    }
+
+    /// How many addresses have been written to
+    pub fn len(&self) -> u128 {
+        self.writes.iter().map(|(pattern, _)| pattern.count()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.writes.is_empty()
+    }
+
+    /// The sum of every value in memory
+    pub fn sum(&self) -> u128 {
+        self.writes
+            .iter()
+            .map(|(pattern, value)| pattern.count() * *value as u128)
+            .sum()
+    }
+
+    /// Lists every address and its value. This enumerates everything, so it's only for small
+    /// memories (and for checking against the enumerating version)
+    pub fn to_hash_map(&self) -> HashMap<usize, usize> {
+        self.writes
+            .iter()
+            .flat_map(|(pattern, value)| pattern.addresses().map(move |address| (address, *value)))
+            .collect()
+    }
+
+    /// Every address and its value in hex, one per line, lowest address first. Like
+    /// `to_hash_map`, this lists every address
+    pub fn dump_hex(&self, width: WordWidth) -> String {
+        let mut values: Vec<(usize, usize)> = self.to_hash_map().into_iter().collect();
+        values.sort_unstable();
+        let digits = width.hex_digits();
+        let mut out = String::new();
+        for (address, value) in values {
+            writeln!(
+                out,
+                "{:0digits$x}: {:0digits$x}",
+                address,
+                value,
+                digits = digits
+            )
+            .unwrap();
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AddressPattern, FloatingMemory};
+    use crate::model::{Part2Mask, WordWidth};
+    use pretty_assertions::assert_eq;
+
+    fn pattern(mask: &str, address: usize) -> AddressPattern {
+        let mask: Part2Mask = format!("mask = {}", mask).parse().unwrap();
+        mask.pattern(address)
+    }
+
+    #[test]
+    fn test_pattern() {
+        let got = pattern("000000000000000000000000000000X1001X", 42);
+        let mut addresses: Vec<usize> = got.addresses().collect();
+        addresses.sort();
+        assert_eq!(addresses, vec![26, 27, 58, 59]);
+        assert_eq!(got.count(), 4);
+        assert!(got.contains(58));
+        assert!(!got.contains(42));
+    }
+
+    #[test]
+    fn test_subtract() {
+        // 0XX minus 01X leaves 00X
+        let a = AddressPattern::new(0b000, 0b011);
+        let b = AddressPattern::new(0b010, 0b001);
+        assert_eq!(a.subtract(&b), vec![AddressPattern::new(0b000, 0b001)]);
+        // Nothing left when `other` covers everything
+        assert_eq!(b.subtract(&a), vec![]);
+        // No overlap, so nothing is taken away
+        let c = AddressPattern::new(0b100, 0b011);
+        assert_eq!(a.subtract(&c), vec![a]);
+        // XXX minus 1X0: the pieces cover the other 6 addresses once each
+        let all = AddressPattern::new(0, 0b111);
+        let cut = AddressPattern::new(0b100, 0b010);
+        let pieces = all.subtract(&cut);
+        let mut addresses: Vec<usize> = pieces.iter().flat_map(|p| p.addresses()).collect();
+        addresses.sort();
+        assert_eq!(addresses, vec![0, 1, 2, 3, 5, 7]);
+    }
+
+    #[test]
+    fn test_intersection() {
+        // 0XX and X1X share 01X
+        let a = AddressPattern::new(0b000, 0b011);
+        let b = AddressPattern::new(0b010, 0b101);
+        assert_eq!(a.intersection(&b), Some(AddressPattern::new(0b010, 0b001)));
+        assert_eq!(a.intersection(&AddressPattern::new(0b100, 0)), None);
+        assert_eq!(b.to_binary(WordWidth::new(4).unwrap()), "0X1X");
+    }
+
+    #[test]
+    fn test_write() {
+        let mut memory = FloatingMemory::new();
+        memory.write(pattern("000000000000000000000000000000X1001X", 42), 100);
+        memory.write(pattern("00000000000000000000000000000000X0XX", 26), 1);
+        assert_eq!(memory.len(), 10);
+        assert_eq!(memory.sum(), 208);
+        assert_eq!(memory.get(26), Some(1));
+        assert_eq!(memory.get(58), Some(100));
+        assert_eq!(memory.get(42), None);
+        let overlap = memory.overlapping(&AddressPattern::new(0b11000, 0b100011));
+        let mut addresses: Vec<(usize, usize)> = overlap
+            .iter()
+            .flat_map(|(piece, value)| piece.addresses().map(move |address| (address, *value)))
+            .collect();
+        addresses.sort();
+        assert_eq!(
+            addresses,
+            vec![(24, 1), (25, 1), (26, 1), (27, 1), (58, 100), (59, 100)]
+        );
+    }
+
+    #[test]
+    fn test_lots_of_wild_bits() {
+        // 2^34 addresses each, far too many to list
+        let mut memory = FloatingMemory::new();
+        memory.write(pattern("XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXX00", 0), 3);
+        memory.write(pattern("XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXX0X", 0), 5);
+        // The second write covers the first, and adds 2^34 more addresses
+        assert_eq!(memory.len(), 1 << 35);
+        assert_eq!(memory.sum(), 5 << 35);
+        memory.write(pattern("00000000000000000000000000000000000X", 0), 7);
+        assert_eq!(memory.sum(), (5 << 35) + 2 * 2);
+    }
+
+    #[test]
+    fn test_dump_hex() {
+        let mut memory = FloatingMemory::new();
+        memory.write(pattern("000000000000000000000000000000X1001X", 42), 100);
+        memory.write(AddressPattern::new(7, 0), 255);
+        let expected = "\
+000000007: 0000000ff
+00000001a: 000000064
+00000001b: 000000064
+00000003a: 000000064
+00000003b: 000000064
+";
+        assert_eq!(memory.dump_hex(WordWidth::default()), expected);
+        let expected = "07: ff\n1a: 64\n1b: 64\n3a: 64\n3b: 64\n";
+        assert_eq!(memory.dump_hex(WordWidth::new(8).unwrap()), expected);
+    }
+}
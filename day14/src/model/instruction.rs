@@ -1,8 +1,16 @@
+use std::fmt;
+
 mod parse;
 
 /// An instruction to write a value to location in memory
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
 pub struct Instruction {
     pub location: usize,
     pub value: usize,
 }
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "mem[{}] = {}", self.location, self.value)
+    }
+}
@@ -8,7 +8,7 @@ impl FromStr for super::Instruction {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         // Example string: mem[8] = 11
-        let parts: Vec<&str> = s.split(|c| c == ' ' || c == '[' || c == ']').collect();
+        let parts: Vec<&str> = s.split([' ', '[', ']']).collect();
         match parts.as_slice() {
             ["mem", location, "", "=", value] => {
                 let location = location.parse().context("Bad Location")?;
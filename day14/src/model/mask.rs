@@ -0,0 +1,86 @@
+//! A `mask = ..` line, keeping every kind of bit so any decoder can use it
+mod parse;
+
+use super::{Bit, BitMask, BitValue, Part2Mask, WordWidth};
+
+/// Which bits of the mask are `1`, `0` and `X`. Each bit position is in at most one of them
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct Mask {
+    ones: usize,
+    zeros: usize,
+    floating: usize,
+}
+
+impl Mask {
+    pub fn new(ones: usize, zeros: usize, floating: usize) -> Mask {
+        Mask {
+            ones,
+            zeros,
+            floating,
+        }
+    }
+
+    /// The `1` bits
+    pub fn ones(&self) -> usize {
+        self.ones
+    }
+
+    /// The `0` bits
+    pub fn zeros(&self) -> usize {
+        self.zeros
+    }
+
+    /// The `X` bits
+    pub fn floating(&self) -> usize {
+        self.floating
+    }
+
+    /// The mask as it would appear in a program, `width` bits long. Bits the mask doesn't mention
+    /// at all are shown as `.`
+    pub fn to_binary(&self, width: WordWidth) -> String {
+        (0..width.bits())
+            .rev()
+            .map(|position| {
+                let bit = 1 << position;
+                if self.ones & bit != 0 {
+                    '1'
+                } else if self.zeros & bit != 0 {
+                    '0'
+                } else if self.floating & bit != 0 {
+                    'X'
+                } else {
+                    '.'
+                }
+            })
+            .collect()
+    }
+
+    /// Every bit the mask says something about
+    pub fn used_bits(&self) -> usize {
+        self.ones | self.zeros | self.floating
+    }
+
+    /// The positions of the set bits in `bits`, smallest first
+    fn positions(bits: usize) -> impl Iterator<Item = usize> {
+        (0..usize::BITS as usize).filter(move |position| bits & (1 << position) != 0)
+    }
+
+    /// The mask as decoder v1 uses it: `0` and `1` overwrite bits of the value
+    pub fn value_mask(&self) -> BitMask {
+        BitMask::new(
+            Mask::positions(self.zeros | self.ones)
+                .map(|position| Bit::new(position, self.ones & (1 << position) != 0))
+                .collect(),
+        )
+    }
+
+    /// The mask as decoder v2 uses it: `1` sets bits of the address and `X` floats them
+    pub fn address_mask(&self) -> Part2Mask {
+        Part2Mask::new(
+            Mask::positions(self.ones)
+                .map(|position| (position, BitValue::On))
+                .chain(Mask::positions(self.floating).map(|position| (position, BitValue::Wild)))
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,77 @@
+use anyhow::{anyhow, Error};
+use std::str::FromStr;
+
+use super::Mask;
+
+impl FromStr for Mask {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Example line
+        // mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X
+        let parts: Vec<&str> = s.split_ascii_whitespace().collect();
+        match parts.as_slice() {
+            ["mask", "=", bits] if bits.len() > usize::BITS as usize => Err(anyhow!(
+                "Mask is {} bits long, but the most we can hold is {}: {}",
+                bits.len(),
+                usize::BITS,
+                s
+            )),
+            ["mask", "=", bits] => {
+                let mut mask = Mask::default();
+                for (position, c) in bits.chars().rev().enumerate() {
+                    let bit = 1 << position;
+                    match c {
+                        '0' => mask.zeros |= bit,
+                        '1' => mask.ones |= bit,
+                        'X' => mask.floating |= bit,
+                        other => return Err(anyhow!("Invalid mask bit '{}' in: {}", other, s)),
+                    }
+                }
+                Ok(mask)
+            }
+            _ => Err(anyhow!("Invalid mask line: {}", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{Bit, BitMask, BitValue, Mask, Part2Mask};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse() {
+        let mask: Mask = "mask = 000000000000000000000000000000X1001X"
+            .parse()
+            .unwrap();
+        assert_eq!(mask.ones(), 0b010010);
+        assert_eq!(mask.floating(), 0b100001);
+        assert_eq!(mask.zeros(), (1 << 36) - 1 - 0b110011);
+        assert!("mask = 01X2".parse::<Mask>().is_err());
+        assert!("mem[8] = 11".parse::<Mask>().is_err());
+        // Too long for a usize
+        assert!(format!("mask = {}", "X".repeat(65))
+            .parse::<Mask>()
+            .is_err());
+        let mask: Mask = format!("mask = {}", "1".repeat(64)).parse().unwrap();
+        assert_eq!(mask.ones(), usize::MAX);
+    }
+
+    #[test]
+    fn test_conversions() {
+        let mask: Mask = "mask = 1X0".parse().unwrap();
+        assert_eq!(
+            mask.value_mask(),
+            BitMask::new(vec![Bit::new(0, false), Bit::new(2, true)])
+        );
+        assert_eq!(
+            mask.address_mask(),
+            Part2Mask::new(
+                vec![(1, BitValue::Wild), (2, BitValue::On)]
+                    .into_iter()
+                    .collect()
+            )
+        );
+    }
+}
@@ -4,6 +4,8 @@ use std::{collections::HashMap, fmt::Debug};
 
 mod parse;
 
+use super::AddressPattern;
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum BitValue {
     // the corresponding memory address bit is overwritten with 1.
@@ -14,15 +16,14 @@ pub enum BitValue {
 
 /// A bitmask, but it's applied to memory locations
 /// A wild bit `X` will write to many locations
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
 pub struct Part2Mask {
     /// All our bits, anything missing is just a 0
     bits: HashMap<usize, BitValue>,
 }
 
 impl Part2Mask {
-    /// Only used so we can mock these up in tests. In production code, they'll only be created using &str::parse();
-    #[cfg(test)]
+    /// Usually you'd parse a `Mask` and call `address_mask` on it instead
     pub fn new(bits: HashMap<usize, BitValue>) -> Part2Mask {
         Part2Mask { bits }
     }
@@ -32,6 +33,19 @@ impl Part2Mask {
     pub fn apply(&self, value: usize) -> Part2MaskIterator {
         Part2MaskIterator::new(&self.bits, value)
     }
+
+    /// All the addresses `apply` would give, as a single pattern
+    pub fn pattern(&self, address: usize) -> AddressPattern {
+        let mut value = address;
+        let mut floating = 0;
+        for (offset, bit) in &self.bits {
+            match bit {
+                BitValue::On => value |= 1 << offset,
+                BitValue::Wild => floating |= 1 << offset,
+            }
+        }
+        AddressPattern::new(value, floating)
+    }
 }
 
 /// Allows you to iterate every state of a wild mask
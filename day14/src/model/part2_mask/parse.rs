@@ -1,39 +1,23 @@
-use anyhow::{anyhow, Error};
-use std::{collections::HashMap, str::FromStr};
+use anyhow::Error;
+use std::str::FromStr;
 
-use super::{BitValue, Part2Mask};
+use crate::model::Mask;
+
+use super::Part2Mask;
 
 impl FromStr for Part2Mask {
     type Err = Error;
 
-    /// Parses the location mask
-    ///
-    /// ```
-    /// ```
+    /// Parses a whole mask line, keeping only the bits decoder v2 uses
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = s.split_ascii_whitespace().collect();
-        match parts.as_slice() {
-            ["mask", "=", mask] => {
-                let bits: HashMap<usize, BitValue> = mask
-                    .chars()
-                    .rev()
-                    .enumerate()
-                    .flat_map(|(position, c)| match &c {
-                        '0' => None,
-                        '1' => Some((position, BitValue::On)),
-                        _ => Some((position, BitValue::Wild)),
-                    })
-                    .collect();
-                Ok(Part2Mask { bits })
-            }
-            _ => Err(anyhow!("Invalid mask line: {}", s)),
-        }
+        let mask: Mask = s.parse()?;
+        Ok(mask.address_mask())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{BitValue, Part2Mask};
+    use crate::model::{BitValue, Part2Mask};
     use pretty_assertions::assert_eq;
 
     #[test]
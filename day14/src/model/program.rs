@@ -0,0 +1,445 @@
+//! A whole docking program, parsed once and run with whichever decoder chip you like
+mod parse;
+
+use std::collections::HashMap;
+
+use super::{
+    Decoder, FloatingMemory, Instruction, Mask, Overwrite, Step, Trace, WidthError, WordWidth,
+};
+
+/// One line of the program
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Line {
+    Mask(Mask),
+    Write(Instruction),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Program {
+    /// Each line with its line number in the source, counting from 1
+    lines: Vec<(usize, Line)>,
+    /// The size of the machine's words. Every value and address has to fit in one
+    width: WordWidth,
+}
+
+impl Program {
+    pub fn new(lines: Vec<(usize, Line)>) -> Program {
+        Program {
+            lines,
+            width: WordWidth::default(),
+        }
+    }
+
+    /// Runs on a machine with `width` bit words instead of 36
+    pub fn with_width(self, width: WordWidth) -> Program {
+        Program { width, ..self }
+    }
+
+    pub fn lines(&self) -> &[(usize, Line)] {
+        &self.lines
+    }
+
+    pub fn width(&self) -> WordWidth {
+        self.width
+    }
+
+    /// Makes sure every mask, value and address in the program fits in a word
+    pub fn check(&self) -> Result<(), WidthError> {
+        let width = self.width.bits();
+        for &(line_number, ref line) in &self.lines {
+            match line {
+                Line::Mask(mask) if !self.width.fits(mask.used_bits()) => {
+                    return Err(WidthError::MaskTooWide {
+                        line: line_number,
+                        width,
+                    })
+                }
+                Line::Write(instruction) => {
+                    self.check_write(line_number, instruction.location, instruction.value)?
+                }
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
+    fn check_write(&self, line: usize, address: usize, value: usize) -> Result<(), WidthError> {
+        let width = self.width.bits();
+        if !self.width.fits(address) {
+            Err(WidthError::AddressTooWide {
+                line,
+                address,
+                width,
+            })
+        } else if !self.width.fits(value) {
+            Err(WidthError::ValueTooWide { line, value, width })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Checks the program, then feeds it through `decoder`, calling `apply` for each write.
+    /// Decoders can't sneak anything too wide past us either
+    fn execute<D: Decoder>(
+        &self,
+        decoder: &mut D,
+        mut apply: impl FnMut(Step),
+    ) -> Result<(), WidthError> {
+        self.check()?;
+        let mut mask = None;
+        for &(line_number, ref line) in &self.lines {
+            match line {
+                Line::Mask(new_mask) => {
+                    decoder.set_mask(new_mask);
+                    mask = Some(*new_mask);
+                }
+                Line::Write(instruction) => {
+                    let write = decoder.decode(instruction);
+                    self.check_write(line_number, write.addresses.highest(), write.value)?;
+                    apply(Step {
+                        line: line_number,
+                        mask,
+                        instruction: *instruction,
+                        write,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs the program on a fresh chip and returns the memory it leaves behind
+    pub fn run<D: Decoder>(&self, decoder: &mut D) -> Result<FloatingMemory, WidthError> {
+        let mut memory = FloatingMemory::new();
+        self.execute(decoder, |step| {
+            memory.write(step.write.addresses, step.write.value)
+        })?;
+        Ok(memory)
+    }
+
+    /// Runs the program, writing to every address one at a time (location => value). Wild bits
+    /// make this slow, but it's easy to check
+    pub fn run_enumerated<D: Decoder>(
+        &self,
+        decoder: &mut D,
+    ) -> Result<HashMap<usize, usize>, WidthError> {
+        let mut memory = HashMap::new();
+        self.execute(decoder, |step| {
+            for address in step.write.addresses.addresses() {
+                memory.insert(address, step.write.value);
+            }
+        })?;
+        Ok(memory)
+    }
+
+    /// Runs the program, recording what every `mem[..] = ..` line did
+    pub fn trace<D: Decoder>(&self, decoder: &mut D) -> Result<Trace, WidthError> {
+        let mut steps = Vec::new();
+        self.execute(decoder, |step| steps.push(step))?;
+        Ok(Trace {
+            width: self.width,
+            steps,
+        })
+    }
+
+    /// Runs the program, recording every time a line writes over addresses an earlier line wrote
+    pub fn overwrites<D: Decoder>(&self, decoder: &mut D) -> Result<Vec<Overwrite>, WidthError> {
+        // Instead of values, this memory holds the line that last wrote to each address
+        let mut owners = FloatingMemory::new();
+        let mut out = Vec::new();
+        self.execute(decoder, |step| {
+            let mut clobbered: Vec<Overwrite> = Vec::new();
+            for (piece, previous) in owners.overlapping(&step.write.addresses) {
+                match clobbered.iter_mut().find(|o| o.overwritten == previous) {
+                    Some(overwrite) => overwrite.addresses.push(piece),
+                    None => clobbered.push(Overwrite {
+                        line: step.line,
+                        overwritten: previous,
+                        addresses: vec![piece],
+                    }),
+                }
+            }
+            clobbered.sort_by_key(|overwrite| overwrite.overwritten);
+            out.extend(clobbered);
+            owners.write(step.write.addresses, step.line);
+        })?;
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Program;
+    use crate::model::{
+        AddressPattern, Decoder, Instruction, Mask, Overwrite, Part2Mask, WidthError, WordWidth,
+        Write, V1, V2,
+    };
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_v1() {
+        let input = "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X
+mem[8] = 11
+mem[7] = 101
+mem[8] = 0";
+        let program: Program = input.parse().unwrap();
+        let memory = program.run(&mut V1::default()).unwrap();
+        assert_eq!(memory.get(8), Some(64));
+        assert_eq!(memory.get(7), Some(101));
+        assert_eq!(memory.len(), 2);
+        assert_eq!(memory.sum(), 165);
+    }
+
+    #[test]
+    fn test_v2() {
+        let input = "mask = 000000000000000000000000000000X1001X
+mem[42] = 100
+mask = 00000000000000000000000000000000X0XX
+mem[26] = 1";
+        let program: Program = input.parse().unwrap();
+        let got = program.run_enumerated(&mut V2::default()).unwrap();
+        let expected: HashMap<usize, usize> = vec![
+            (16, 1),
+            (17, 1),
+            (18, 1),
+            (19, 1),
+            (24, 1),
+            (25, 1),
+            (26, 1),
+            (27, 1),
+            // 26 and 27 got 100 first, but then 1 overwrote it
+            (58, 100),
+            (59, 100),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(got, expected);
+        let memory = program.run(&mut V2::default()).unwrap();
+        assert_eq!(memory.to_hash_map(), expected);
+        assert_eq!(memory.sum(), 208);
+    }
+
+    /// Runs a v2 program the slow way, with `Part2Mask::apply` listing every address
+    fn brute_force(input: &str) -> HashMap<usize, usize> {
+        let mut memory = HashMap::new();
+        let mut mask = Part2Mask::default();
+        for line in input.lines() {
+            if line.starts_with("mask") {
+                mask = line.parse().unwrap();
+            } else {
+                let instruction: Instruction = line.parse().unwrap();
+                for address in mask.apply(instruction.location) {
+                    memory.insert(address, instruction.value);
+                }
+            }
+        }
+        memory
+    }
+
+    #[test]
+    fn test_floating_matches_brute_force() {
+        let programs = [
+            // Small writes landing inside a big one
+            "mask = XXXX0000\nmem[0] = 5\nmask = 000000X1\nmem[8] = 7\nmask = 00000000\nmem[48] = 9",
+            // A big write covering everything written before it
+            "mask = 0000000X\nmem[2] = 1\nmem[6] = 2\nmask = 00000XXX\nmem[0] = 3",
+            // Patterns that only partly overlap, then a third cutting across both
+            "mask = 0000XX0X\nmem[0] = 1\nmask = 00000X1X\nmem[0] = 2\nmask = 00001XX0\nmem[0] = 3",
+            // The same pattern written twice, and a write that misses everything
+            "mask = 0000X0X0\nmem[1] = 4\nmem[1] = 6\nmask = 1000000X\nmem[0] = 8",
+            // Lots of small overlapping writes, each one splitting up the ones before
+            "mask = 0000XXXX\nmem[0] = 1\nmask = 00000XX0\nmem[1] = 2\nmask = 0000X00X\nmem[2] = 3\nmask = 000000XX\nmem[4] = 4\nmask = 00000000\nmem[15] = 5",
+        ];
+        for input in &programs {
+            let expected = brute_force(input);
+            let program: Program = input.parse().unwrap();
+            let memory = program.run(&mut V2::default()).unwrap();
+            assert_eq!(memory.to_hash_map(), expected, "{}", input);
+            assert_eq!(memory.sum(), expected.values().sum::<usize>() as u128);
+            assert_eq!(memory.len(), expected.len() as u128);
+        }
+    }
+
+    /// A chip from some other ferry: it writes the value to the address and the one after it,
+    /// ignoring the mask completely
+    struct Doubler;
+
+    impl Decoder for Doubler {
+        fn set_mask(&mut self, _mask: &Mask) {}
+
+        fn decode(&self, instruction: &Instruction) -> Write {
+            Write {
+                addresses: AddressPattern::new(instruction.location & !1, 1),
+                value: instruction.value,
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_decoder() {
+        let input = "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X
+mem[8] = 11
+mem[9] = 5";
+        let program: Program = input.parse().unwrap();
+        let memory = program.run(&mut Doubler).unwrap();
+        assert_eq!(memory.get(8), Some(5));
+        assert_eq!(memory.get(9), Some(5));
+        assert_eq!(memory.sum(), 10);
+    }
+
+    #[test]
+    fn test_width() {
+        // Blank lines still count towards the line number
+        let program: Program = "mask = 1XX0\n\nmem[8] = 15\n\nmem[7] = 16".parse().unwrap();
+        let program = program.with_width(WordWidth::new(4).unwrap());
+        assert_eq!(
+            program.run(&mut V1::default()),
+            Err(WidthError::ValueTooWide {
+                line: 5,
+                value: 16,
+                width: 4
+            })
+        );
+        let program: Program = "mask = 1XX0\nmem[16] = 1".parse().unwrap();
+        let program = program.with_width(WordWidth::new(4).unwrap());
+        assert_eq!(
+            program.run(&mut V2::default()),
+            Err(WidthError::AddressTooWide {
+                line: 2,
+                address: 16,
+                width: 4
+            })
+        );
+        let program: Program = "mask = 01XX0\nmem[1] = 1".parse().unwrap();
+        let program = program.with_width(WordWidth::new(4).unwrap());
+        assert_eq!(
+            program.check(),
+            Err(WidthError::MaskTooWide { line: 1, width: 4 })
+        );
+        // Everything fits in 5 bits
+        let program = program.with_width(WordWidth::new(5).unwrap());
+        assert_eq!(program.run(&mut V2::default()).unwrap().sum(), 4);
+    }
+
+    #[test]
+    fn test_decoder_overflow() {
+        // A chip that shifts everything left, so an address can fit going in and not coming out
+        struct Shifter;
+        impl Decoder for Shifter {
+            fn set_mask(&mut self, _mask: &Mask) {}
+
+            fn decode(&self, instruction: &Instruction) -> Write {
+                Write {
+                    addresses: AddressPattern::new(instruction.location << 1, 0),
+                    value: instruction.value << 1,
+                }
+            }
+        }
+        let program: Program = "mem[7] = 1\nmem[8] = 1".parse().unwrap();
+        let program = program.with_width(WordWidth::new(4).unwrap());
+        assert_eq!(
+            program.run(&mut Shifter),
+            Err(WidthError::AddressTooWide {
+                line: 2,
+                address: 16,
+                width: 4
+            })
+        );
+    }
+
+    #[test]
+    fn test_trace() {
+        let input = "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X
+mem[8] = 11";
+        let program: Program = input.parse().unwrap();
+        let trace = program.trace(&mut V1::default()).unwrap();
+        let expected = "\
+line 2: mem[8] = 11
+  mask    XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X
+  value   000000000000000000000000000000001011 (11)
+  masked  000000000000000000000000000001001001 (73)
+  address 000000000000000000000000000000001000 (address 8)
+";
+        assert_eq!(trace.to_string(), expected);
+        let input = "mem[3] = 1
+mask = 000000000000000000000000000000X1001X
+mem[42] = 100
+mask = 0000000000000000000000000000XXXXXXXX
+mem[0] = 5";
+        let program: Program = input.parse().unwrap();
+        let trace = program.trace(&mut V2::default()).unwrap();
+        let expected = "\
+line 1: mem[3] = 1
+  mask    (none)
+  value   000000000000000000000000000000000001 (1)
+  masked  000000000000000000000000000000000001 (1)
+  address 000000000000000000000000000000000011 (address 3)
+line 3: mem[42] = 100
+  mask    000000000000000000000000000000X1001X
+  value   000000000000000000000000000001100100 (100)
+  masked  000000000000000000000000000001100100 (100)
+  address 000000000000000000000000000000X1101X (4 addresses: 26, 27, 58, 59)
+line 5: mem[0] = 5
+  mask    0000000000000000000000000000XXXXXXXX
+  value   000000000000000000000000000000000101 (5)
+  masked  000000000000000000000000000000000101 (5)
+  address 0000000000000000000000000000XXXXXXXX (256 addresses)
+";
+        assert_eq!(trace.to_string(), expected);
+    }
+
+    #[test]
+    fn test_overwrites() {
+        let input = "mask = 000000000000000000000000000000X1001X
+mem[42] = 100
+mask = 00000000000000000000000000000000X0XX
+mem[26] = 1
+mask = 000000000000000000000000000000000000
+mem[26] = 2
+mem[7] = 3
+mask = 0000000000000000000000000000XXXXXXXX
+mem[0] = 4";
+        let program: Program = input.parse().unwrap();
+        let overwrites = program.overwrites(&mut V2::default()).unwrap();
+        let report: Vec<String> = overwrites.iter().map(|o| o.to_string()).collect();
+        assert_eq!(
+            report,
+            vec![
+                "line 4 overwrote line 2 at 2 addresses: 26, 27",
+                "line 6 overwrote line 4 at address 26",
+                "line 9 overwrote line 2 at 2 addresses: 58, 59",
+                "line 9 overwrote line 4 at 7 addresses: 16, 17, 18, 19, 24, 25, 27",
+                "line 9 overwrote line 6 at address 26",
+                "line 9 overwrote line 7 at address 7",
+            ]
+        );
+        assert_eq!(overwrites[3].count(), 7);
+        assert_eq!(
+            overwrites[2],
+            Overwrite {
+                line: 9,
+                overwritten: 2,
+                addresses: vec![AddressPattern::new(58, 1)]
+            }
+        );
+    }
+
+    #[test]
+    fn test_blank_lines() {
+        // Steps and overwrites point at the line in the file, blank ones and all
+        let input = "
+mask = 00000000000000000000000000000000000X
+mem[2] = 1
+
+mem[3] = 2
+";
+        let program: Program = input.parse().unwrap();
+        let trace = program.trace(&mut V2::default()).unwrap();
+        let lines: Vec<usize> = trace.steps.iter().map(|step| step.line).collect();
+        assert_eq!(lines, vec![3, 5]);
+        let overwrites = program.overwrites(&mut V2::default()).unwrap();
+        let report: Vec<String> = overwrites.iter().map(|o| o.to_string()).collect();
+        assert_eq!(report, vec!["line 5 overwrote line 3 at 2 addresses: 2, 3"]);
+    }
+}
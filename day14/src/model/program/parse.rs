@@ -0,0 +1,90 @@
+use anyhow::{Context, Error};
+use std::str::FromStr;
+
+use super::{Line, Program};
+
+impl FromStr for Line {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with("mask") {
+            Ok(Line::Mask(s.parse()?))
+        } else {
+            Ok(Line::Write(s.parse()?))
+        }
+    }
+}
+
+impl FromStr for Program {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines: Result<Vec<(usize, Line)>, Error> = s
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| -> Result<(usize, Line), _> {
+                let parsed =
+                    line.parse()
+                        .context(format!("Unable to parse line {}: {}", i + 1, line))?;
+                Ok((i + 1, parsed))
+            })
+            .collect();
+        Ok(Program::new(lines?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{Instruction, Line, Mask, Program};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse() {
+        let input = "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X
+mem[8] = 11
+
+mask = 0000000000000000000000000000010000X0
+mem[7] = 101
+";
+        let got: Program = input.parse().unwrap();
+        assert_eq!(
+            got.lines(),
+            &[
+                (
+                    1,
+                    Line::Mask(Mask::new(
+                        1 << 6,
+                        1 << 1,
+                        (1 << 36) - 1 - (1 << 6) - (1 << 1)
+                    ))
+                ),
+                (
+                    2,
+                    Line::Write(Instruction {
+                        location: 8,
+                        value: 11
+                    })
+                ),
+                // Line 3 is blank
+                (
+                    4,
+                    Line::Mask(Mask::new(
+                        1 << 6,
+                        (1 << 36) - 1 - (1 << 6) - (1 << 1),
+                        1 << 1
+                    ))
+                ),
+                (
+                    5,
+                    Line::Write(Instruction {
+                        location: 7,
+                        value: 101
+                    })
+                ),
+            ]
+        );
+        let error = "mask = 01\nmem[8] = x".parse::<Program>().unwrap_err();
+        assert_eq!(error.to_string(), "Unable to parse line 2: mem[8] = x");
+    }
+}
@@ -0,0 +1,107 @@
+//! What a program did, line by line, for when the sum isn't what we expected
+
+use std::fmt;
+
+use super::{AddressPattern, Instruction, Mask, WordWidth, Write};
+
+/// Writes to more addresses than this just show how many there were
+const ADDRESS_LIMIT: u128 = 8;
+
+/// Lists the addresses in `patterns` in order, or just counts them if there are too many
+fn describe_addresses(patterns: &[AddressPattern]) -> String {
+    let count: u128 = patterns.iter().map(|pattern| pattern.count()).sum();
+    if count > ADDRESS_LIMIT {
+        return format!("{} addresses", count);
+    }
+    let mut addresses: Vec<usize> = patterns
+        .iter()
+        .flat_map(|pattern| pattern.addresses())
+        .collect();
+    addresses.sort_unstable();
+    let addresses: Vec<String> = addresses.iter().map(|a| a.to_string()).collect();
+    match count {
+        1 => format!("address {}", addresses[0]),
+        _ => format!("{} addresses: {}", count, addresses.join(", ")),
+    }
+}
+
+/// One `mem[..] = ..` line, and what the decoder made of it
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Step {
+    /// The line number in the source, counting from 1
+    pub line: usize,
+    /// The mask at the time. None if there hasn't been one yet
+    pub mask: Option<Mask>,
+    pub instruction: Instruction,
+    pub write: Write,
+}
+
+impl Step {
+    /// The step over a few lines, with every number `width` bits long
+    pub fn render(&self, width: WordWidth) -> String {
+        let bits = width.bits() as usize;
+        let mask = match self.mask {
+            Some(mask) => mask.to_binary(width),
+            None => "(none)".to_string(),
+        };
+        format!(
+            "line {}: {}\n  mask    {}\n  value   {:0bits$b} ({})\n  masked  {:0bits$b} ({})\n  address {} ({})\n",
+            self.line,
+            self.instruction,
+            mask,
+            self.instruction.value,
+            self.instruction.value,
+            self.write.value,
+            self.write.value,
+            self.write.addresses.to_binary(width),
+            describe_addresses(&[self.write.addresses]),
+            bits = bits
+        )
+    }
+}
+
+/// Every step a program took
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Trace {
+    pub width: WordWidth,
+    pub steps: Vec<Step>,
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for step in &self.steps {
+            write!(f, "{}", step.render(self.width))?;
+        }
+        Ok(())
+    }
+}
+
+/// Addresses that one line wrote to, and a later line wrote to again
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Overwrite {
+    /// The line that did the overwriting
+    pub line: usize,
+    /// The line whose values got overwritten
+    pub overwritten: usize,
+    /// The addresses that changed hands, as patterns that don't overlap
+    pub addresses: Vec<AddressPattern>,
+}
+
+impl Overwrite {
+    /// How many addresses were overwritten
+    pub fn count(&self) -> u128 {
+        self.addresses.iter().map(|pattern| pattern.count()).sum()
+    }
+}
+
+impl fmt::Display for Overwrite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {} overwrote line {} at {}",
+            self.line,
+            self.overwritten,
+            describe_addresses(&self.addresses)
+        )
+    }
+}
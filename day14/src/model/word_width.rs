@@ -0,0 +1,65 @@
+//! How many bits the docking computer's memory words have
+
+use super::WidthError;
+
+/// The number of bits in a value or an address. The puzzle's machine uses 36
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct WordWidth {
+    bits: u32,
+}
+
+impl Default for WordWidth {
+    fn default() -> Self {
+        WordWidth { bits: 36 }
+    }
+}
+
+impl WordWidth {
+    /// Fails unless `bits` is between 1 and 64 (the size of a usize)
+    pub fn new(bits: u32) -> Result<WordWidth, WidthError> {
+        if bits == 0 || bits > usize::BITS {
+            Err(WidthError::InvalidWidth(bits))
+        } else {
+            Ok(WordWidth { bits })
+        }
+    }
+
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    /// The biggest number that fits in a word
+    pub fn max(&self) -> usize {
+        usize::MAX >> (usize::BITS - self.bits)
+    }
+
+    /// True if `n` has no bits past the end of the word
+    pub fn fits(&self, n: usize) -> bool {
+        n <= self.max()
+    }
+
+    /// How many hex digits it takes to write any word
+    pub fn hex_digits(&self) -> usize {
+        self.bits.div_ceil(4) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WordWidth;
+    use crate::model::WidthError;
+
+    #[test]
+    fn test_width() {
+        let width = WordWidth::default();
+        assert_eq!(width.bits(), 36);
+        assert_eq!(width.max(), (1 << 36) - 1);
+        assert!(width.fits((1 << 36) - 1));
+        assert!(!width.fits(1 << 36));
+        assert_eq!(width.hex_digits(), 9);
+        assert_eq!(WordWidth::new(64).unwrap().max(), usize::MAX);
+        assert_eq!(WordWidth::new(1).unwrap().max(), 1);
+        assert_eq!(WordWidth::new(0), Err(WidthError::InvalidWidth(0)));
+        assert_eq!(WordWidth::new(65), Err(WidthError::InvalidWidth(65)));
+    }
+}
@@ -0,0 +1,166 @@
+//! Turns hand written boot code into instructions, and back again
+//!
+//! Source format:
+//!
+//! ```text
+//! # Comments start with `#` or `;` and run to the end of the line
+//! start:          ; A label names the next instruction
+//!     acc +3
+//!     mul 2       ; The `+` is optional
+//!     jnz start   ; Jump operands can be labels
+//!     hlt
+//! ```
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::Instruction;
+
+/// Strips a comment off the end of a line
+fn strip_comment(line: &str) -> &str {
+    line.split(['#', ';']).next().unwrap_or("")
+}
+
+/// Splits a leading `label:` off a line. Returns the label (if any) and the rest of the line
+fn split_label(line: &str) -> (Option<&str>, &str) {
+    match line.find(':') {
+        Some(colon) => (Some(line[..colon].trim()), line[colon + 1..].trim()),
+        None => (None, line),
+    }
+}
+
+/// Assembles source code with labels and comments into a program
+pub fn assemble(source: &str) -> Result<Vec<Instruction>> {
+    // First pass: find out which instruction each label points at
+    let mut labels = HashMap::new();
+    let mut lines = Vec::new();
+    for (line_number, line) in source.lines().enumerate() {
+        let (label, code) = split_label(strip_comment(line).trim());
+        if let Some(label) = label {
+            if label.is_empty() || label.contains(char::is_whitespace) {
+                return Err(anyhow!("Line {}: Bad label: {:?}", line_number + 1, label));
+            }
+            if labels.insert(label, lines.len()).is_some() {
+                return Err(anyhow!(
+                    "Line {}: Duplicate label: {}",
+                    line_number + 1,
+                    label
+                ));
+            }
+        }
+        if !code.is_empty() {
+            lines.push((line_number, code));
+        }
+    }
+    // Second pass: replace label operands with relative offsets and parse
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(index, (line_number, code))| {
+            let mut parts = code.split_whitespace();
+            let opcode = parts.next().unwrap_or("");
+            let canonical = match (parts.next(), parts.next()) {
+                (None, _) => opcode.to_string(),
+                (Some(operand), None) => {
+                    let offset = match operand.parse::<i64>() {
+                        Ok(num) => num,
+                        // Only jumps can use a label, anything else needs a number
+                        Err(_) if !["jmp", "jnz", "nop"].contains(&opcode) => {
+                            return Err(anyhow!(
+                                "Line {}: {} needs a number, not {}",
+                                line_number + 1,
+                                opcode,
+                                operand
+                            ))
+                        }
+                        Err(_) => {
+                            let target = labels.get(operand).ok_or_else(|| {
+                                anyhow!("Line {}: Unknown label: {}", line_number + 1, operand)
+                            })?;
+                            *target as i64 - index as i64
+                        }
+                    };
+                    format!("{} {:+}", opcode, offset)
+                }
+                _ => return Err(anyhow!("Line {}: Too many operands", line_number + 1)),
+            };
+            canonical
+                .parse()
+                .map_err(|_| anyhow!("Unknown instruction: {}", canonical))
+                .with_context(|| format!("Line {}: {}", line_number + 1, code))
+        })
+        .collect()
+}
+
+/// Writes a program out in the canonical puzzle input format, one instruction per line
+pub fn disassemble(program: &[Instruction]) -> String {
+    program
+        .iter()
+        .map(|instruction| format!("{}\n", instruction))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse, Instruction, Machine, RunResult};
+
+    #[test]
+    fn test_assemble() -> anyhow::Result<()> {
+        let source = r#"
+# Skips the big add if the accumulator is set
+start:  acc +3
+        jnz end     ; skip it
+        acc +100
+end:    hlt
+        jmp start
+"#;
+        let got = super::assemble(source)?;
+        let expected = vec![
+            Instruction::Acc(3),
+            Instruction::Jnz(2),
+            Instruction::Acc(100),
+            Instruction::Hlt,
+            Instruction::Jmp(-4),
+        ];
+        assert_eq!(got, expected);
+        assert_eq!(
+            Machine::new(&got).run(),
+            RunResult::Halted { ip: 3, acc: 3 }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_forward_label() -> anyhow::Result<()> {
+        let got = super::assemble("jmp skip\nmul 5\nskip: nop 0")?;
+        let expected = vec![
+            Instruction::Jmp(2),
+            Instruction::Mul(5),
+            Instruction::Nop(0),
+        ];
+        assert_eq!(got, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(super::assemble("jmp nowhere").is_err());
+        assert!(super::assemble("a: nop +0\na: nop +0").is_err());
+        assert!(super::assemble("div +2").is_err());
+        assert!(super::assemble("acc +1 +2").is_err());
+        // Labels are only for jumps
+        assert!(super::assemble("start: acc start").is_err());
+        assert!(super::assemble("mul end\nend: hlt").is_err());
+        assert!(super::assemble("nop end\nend: hlt").is_ok());
+    }
+
+    #[test]
+    fn test_round_trip() -> anyhow::Result<()> {
+        let input = "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6\n";
+        let program = parse(input);
+        assert_eq!(super::disassemble(&program), input);
+        assert_eq!(super::assemble(&super::disassemble(&program))?, program);
+        Ok(())
+    }
+}
@@ -0,0 +1,224 @@
+//! An interactive debugger for boot code, driven by line based commands
+
+use std::io::{self, BufRead, Write};
+
+use parse_display::{Display, FromStr};
+
+use crate::{Instruction, Machine};
+
+/// Somewhere we want `continue` to stop
+#[derive(Display, FromStr, PartialEq, Debug, Clone, Copy)]
+pub enum Breakpoint {
+    /// Stop before executing the instruction at this index
+    #[display("ip {0}")]
+    Ip(usize),
+    /// Stop as soon as the accumulator changes to this value
+    #[display("acc {0}")]
+    Acc(i64),
+}
+
+/// A line of debugger input
+#[derive(Display, FromStr, PartialEq, Debug)]
+pub enum Command {
+    /// Execute one instruction
+    #[display("step")]
+    Step,
+    /// Run until a breakpoint is hit or the machine stops
+    #[display("continue")]
+    Continue,
+    /// Add a breakpoint
+    #[display("break {0}")]
+    Break(Breakpoint),
+    /// Remove a breakpoint
+    #[display("delete {0}")]
+    Delete(Breakpoint),
+    /// Show the machine state
+    #[display("inspect")]
+    Inspect,
+    /// Show the available commands
+    #[display("help")]
+    Help,
+    /// Stop debugging
+    #[display("quit")]
+    Quit,
+}
+
+const HELP: &str = r#"Commands:
+  step              execute one instruction
+  continue          run until a breakpoint or the program stops
+  break ip <n>      stop before executing instruction <n>
+  break acc <n>     stop when the accumulator changes to <n>
+  delete ip <n>     remove a breakpoint (same for acc)
+  inspect           show ip, acc, breakpoints and the next instruction
+  help              show this text
+  quit              stop debugging"#;
+
+/// Holds a machine and the breakpoints set on it
+pub struct Debugger<'a> {
+    machine: Machine<'a>,
+    breakpoints: Vec<Breakpoint>,
+}
+
+impl<'a> Debugger<'a> {
+    pub fn new(program: &'a [Instruction]) -> Debugger<'a> {
+        Debugger {
+            machine: Machine::new(program),
+            breakpoints: Vec::new(),
+        }
+    }
+
+    /// The machine being debugged
+    pub fn machine(&self) -> &Machine<'a> {
+        &self.machine
+    }
+
+    /// Executes a single instruction, writing what happened to `out`.
+    /// Returns false if the machine has stopped
+    fn step(&mut self, out: &mut impl Write) -> io::Result<bool> {
+        match self.machine.step_traced() {
+            Ok(entry) => {
+                writeln!(out, "{}", entry)?;
+                Ok(true)
+            }
+            Err(result) => {
+                writeln!(out, "Stopped: {:?}", result)?;
+                Ok(false)
+            }
+        }
+    }
+
+    /// Returns the breakpoint that the machine is currently sitting on, if any
+    fn hit_breakpoint(&self, acc_before: i64) -> Option<Breakpoint> {
+        let ip = self.machine.ip();
+        let acc = self.machine.acc();
+        self.breakpoints
+            .iter()
+            .cloned()
+            .find(|breakpoint| match *breakpoint {
+                Breakpoint::Ip(index) => ip == index as i64,
+                Breakpoint::Acc(value) => acc != acc_before && acc == value,
+            })
+    }
+
+    /// Handles one command. Returns false once the user wants to quit
+    pub fn execute(&mut self, command: Command, out: &mut impl Write) -> io::Result<bool> {
+        match command {
+            Command::Step => {
+                self.step(out)?;
+            }
+            Command::Continue => loop {
+                let acc_before = self.machine.acc();
+                if !self.step(out)? {
+                    break;
+                }
+                if let Some(breakpoint) = self.hit_breakpoint(acc_before) {
+                    writeln!(out, "Breakpoint: {}", breakpoint)?;
+                    break;
+                }
+            },
+            Command::Break(breakpoint) => {
+                if !self.breakpoints.contains(&breakpoint) {
+                    self.breakpoints.push(breakpoint);
+                }
+            }
+            Command::Delete(breakpoint) => self.breakpoints.retain(|b| *b != breakpoint),
+            Command::Inspect => {
+                writeln!(out, "ip: {}", self.machine.ip())?;
+                writeln!(out, "acc: {}", self.machine.acc())?;
+                writeln!(out, "executed: {}", self.machine.visited().len())?;
+                for breakpoint in &self.breakpoints {
+                    writeln!(out, "breakpoint: {}", breakpoint)?;
+                }
+                match self.machine.status() {
+                    Some(result) => writeln!(out, "stopped: {:?}", result)?,
+                    None => writeln!(
+                        out,
+                        "next: {}",
+                        self.machine.program()[self.machine.ip() as usize]
+                    )?,
+                }
+            }
+            Command::Help => writeln!(out, "{}", HELP)?,
+            Command::Quit => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Reads commands from `input` (one per line) until it runs out or we get `quit`
+    pub fn run(&mut self, input: impl BufRead, mut out: impl Write) -> io::Result<()> {
+        write!(out, "(day8) ")?;
+        out.flush()?;
+        for line in input.lines() {
+            let line = line?;
+            let line = line.trim();
+            if !line.is_empty() {
+                match line.parse() {
+                    Ok(command) => {
+                        if !self.execute(command, &mut out)? {
+                            return Ok(());
+                        }
+                    }
+                    Err(_) => writeln!(out, "Unknown command: {} (try `help`)", line)?,
+                }
+            }
+            write!(out, "(day8) ")?;
+            out.flush()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Breakpoint, Command, Debugger};
+    use crate::parse;
+
+    fn program() -> Vec<crate::Instruction> {
+        parse(
+            r#"nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6"#,
+        )
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!("step".parse(), Ok(Command::Step));
+        assert_eq!("break ip 4".parse(), Ok(Command::Break(Breakpoint::Ip(4))));
+        assert_eq!(
+            "delete acc -3".parse(),
+            Ok(Command::Delete(Breakpoint::Acc(-3)))
+        );
+    }
+
+    #[test]
+    fn test_breakpoints() {
+        let program = program();
+        let mut debugger = Debugger::new(&program);
+        let input = "break ip 6\ncontinue\nbreak acc 5\ncontinue\nquit\nstep\n";
+        let mut out = Vec::new();
+        debugger.run(input.as_bytes(), &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("Breakpoint: ip 6"));
+        assert!(out.contains("Breakpoint: acc 5"));
+        // Stopped at acc +3 (index 3), and quit before the last step
+        assert_eq!(debugger.machine().ip(), 4);
+        assert_eq!(debugger.machine().acc(), 5);
+    }
+
+    #[test]
+    fn test_continue_to_loop() {
+        let program = program();
+        let mut debugger = Debugger::new(&program);
+        let mut out = Vec::new();
+        debugger.run("continue\n".as_bytes(), &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("LoopDetected { ip: 1, acc: 5 }"));
+    }
+}
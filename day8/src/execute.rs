@@ -0,0 +1,59 @@
+//! Runs the boot code for each part of the puzzle
+
+use crate::{Instruction, Machine, RunResult};
+
+/// Executes the program, but stops as soon as we hit the same instruction twice
+pub fn execute_part1(instructions: &[Instruction]) -> i64 {
+    Machine::new(instructions).run().acc()
+}
+
+/// Executes until the program is done.
+/// If it gets to the end of the program it returs the accumulator value
+/// If it infinite loops, or jumps out of the program, it returns None
+pub fn execute_part2(instructions: &[Instruction]) -> Option<i64> {
+    match Machine::new(instructions).run() {
+        RunResult::Terminated { acc } => Some(acc),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse, Instruction};
+
+    fn input() -> &'static str {
+        r#"nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6"#
+    }
+
+    #[test]
+    fn test_execute_part1() {
+        let instructions = parse(input());
+        let got = super::execute_part1(&instructions);
+        let expected = 5;
+        assert_eq!(got, expected);
+    }
+
+    #[test]
+    fn test_execute_part2() {
+        let mut instructions = parse(input());
+        let penultimate_index = instructions.len() - 2;
+        instructions[penultimate_index] = Instruction::Nop(0);
+        let got = super::execute_part2(&instructions);
+        let expected = Some(8);
+        assert_eq!(got, expected);
+    }
+
+    #[test]
+    fn test_execute_part2_loops() {
+        let instructions = parse(input());
+        assert_eq!(super::execute_part2(&instructions), None);
+    }
+}
@@ -0,0 +1,127 @@
+//! The boot code instruction set
+
+use std::fmt;
+
+use parse_display::FromStr;
+
+#[derive(FromStr, PartialEq, Debug, Clone, Copy)]
+#[display(style = "snake_case")]
+pub enum Instruction {
+    #[display("acc {0}")]
+    Acc(i64),
+    #[display("nop {0}")]
+    Nop(i64),
+    #[display("jmp {0}")]
+    Jmp(i64),
+    /// Jumps if the accumulator is not zero, otherwise does nothing
+    #[display("jnz {0}")]
+    Jnz(i64),
+    /// Multiplies the accumulator
+    #[display("mul {0}")]
+    Mul(i64),
+    /// Stops the machine
+    #[display("hlt")]
+    Hlt,
+}
+
+/// Writes the canonical format, eg. `jmp +4`, `acc -99`
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Instruction::Acc(num) => write!(f, "acc {:+}", num),
+            Instruction::Nop(num) => write!(f, "nop {:+}", num),
+            Instruction::Jmp(num) => write!(f, "jmp {:+}", num),
+            Instruction::Jnz(num) => write!(f, "jnz {:+}", num),
+            Instruction::Mul(num) => write!(f, "mul {:+}", num),
+            Instruction::Hlt => write!(f, "hlt"),
+        }
+    }
+}
+
+impl Instruction {
+    /// Returns true if this is a jmp instruction
+    pub fn is_jmp(&self) -> bool {
+        matches!(self, Instruction::Jmp(_))
+    }
+    /// Flips a jmp instruction to a nop instruction
+    pub fn flip_to_nop(&mut self) {
+        if let Instruction::Jmp(val) = *self {
+            *self = Instruction::Nop(val)
+        }
+    }
+    /// Flips a nop instruction to a jmp instruction
+    pub fn flip_to_jmp(&mut self) {
+        if let Instruction::Nop(val) = *self {
+            *self = Instruction::Jmp(val)
+        }
+    }
+    /// Returns the jmp/nop flipped version of this instruction, or None for anything else
+    pub fn flipped(&self) -> Option<Instruction> {
+        match *self {
+            Instruction::Jmp(val) => Some(Instruction::Nop(val)),
+            Instruction::Nop(val) => Some(Instruction::Jmp(val)),
+            _ => None,
+        }
+    }
+    /// Every place the instruction pointer could go after executing this instruction at `ip`
+    pub fn successors(&self, ip: i64) -> Vec<i64> {
+        match *self {
+            Instruction::Jmp(num) => vec![ip + num],
+            Instruction::Jnz(num) => vec![ip + 1, ip + num],
+            Instruction::Acc(_) | Instruction::Nop(_) | Instruction::Mul(_) => vec![ip + 1],
+            Instruction::Hlt => vec![],
+        }
+    }
+}
+
+/// Parses input rules (one per line) and returns the instructions, or panics
+pub fn parse(input: &str) -> Vec<Instruction> {
+    input
+        .lines()
+        .map(|i| -> Instruction {
+            i.parse()
+                .unwrap_or_else(|_| panic!("Unable to parse instruction: {}", i))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Instruction;
+
+    #[test]
+    fn test_parsing() {
+        let input = r#"nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6"#;
+        let expected = vec![
+            Instruction::Nop(0),
+            Instruction::Acc(1),
+            Instruction::Jmp(4),
+            Instruction::Acc(3),
+            Instruction::Jmp(-3),
+            Instruction::Acc(-99),
+            Instruction::Acc(1),
+            Instruction::Jmp(-4),
+            Instruction::Acc(6),
+        ];
+        let got = super::parse(input);
+        assert_eq!(got, expected);
+    }
+
+    #[test]
+    fn test_display() {
+        let input = "nop +0\nacc -99\njnz +3\nmul -2\nhlt";
+        let got: Vec<String> = super::parse(input)
+            .iter()
+            .map(|instruction| instruction.to_string())
+            .collect();
+        assert_eq!(got.join("\n"), input);
+    }
+}
@@ -0,0 +1,17 @@
+mod instruction;
+pub use instruction::{parse, Instruction};
+
+mod machine;
+pub use machine::{Machine, RunResult, TraceEntry};
+
+mod execute;
+pub use execute::{execute_part1, execute_part2};
+
+mod repair;
+pub use repair::{find_repair, ControlFlow, Repair};
+
+mod debugger;
+pub use debugger::{Breakpoint, Command, Debugger};
+
+mod assembler;
+pub use assembler::{assemble, disassemble};
@@ -0,0 +1,356 @@
+//! The handheld console virtual machine
+
+use std::{collections::HashSet, fmt};
+
+use crate::Instruction;
+
+/// Why the machine stopped running
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum RunResult {
+    /// The instruction pointer landed just past the last instruction
+    Terminated { acc: i64 },
+    /// We were about to execute the instruction at `ip` for the second time (with the same
+    /// accumulator, if the program has `jnz` in it)
+    LoopDetected { ip: usize, acc: i64 },
+    /// A jump sent the instruction pointer somewhere outside the program
+    JumpedOutOfBounds { ip: i64, acc: i64 },
+    /// We executed the `hlt` instruction at `ip`
+    Halted { ip: usize, acc: i64 },
+    /// The instruction at `ip` would have pushed the accumulator past what an i64 can hold
+    Overflowed { ip: usize, acc: i64 },
+}
+
+impl RunResult {
+    /// The value of the accumulator when the machine stopped
+    pub fn acc(&self) -> i64 {
+        match *self {
+            RunResult::Terminated { acc }
+            | RunResult::LoopDetected { acc, .. }
+            | RunResult::JumpedOutOfBounds { acc, .. }
+            | RunResult::Halted { acc, .. }
+            | RunResult::Overflowed { acc, .. } => acc,
+        }
+    }
+}
+
+/// A record of one executed instruction
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct TraceEntry {
+    /// Where the instruction was
+    pub ip: usize,
+    /// What was executed
+    pub instruction: Instruction,
+    /// The accumulator before execution
+    pub acc_before: i64,
+    /// The accumulator after execution
+    pub acc_after: i64,
+    /// Where the instruction pointer went next
+    pub next_ip: i64,
+}
+
+impl fmt::Display for TraceEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>5}: {:<10} acc {} -> {}, ip -> {}",
+            self.ip,
+            self.instruction.to_string(),
+            self.acc_before,
+            self.acc_after,
+            self.next_ip
+        )
+    }
+}
+
+/// Runs boot code one instruction at a time
+pub struct Machine<'a> {
+    /// The program we're running
+    program: &'a [Instruction],
+    /// The instruction pointer
+    ip: i64,
+    /// The accumulator
+    acc: i64,
+    /// Every instruction we've executed so far
+    visited: HashSet<usize>,
+    /// Every (ip, acc) we've executed an instruction in so far
+    states: HashSet<(usize, i64)>,
+    /// True if the accumulator can change where the program goes (it has a `jnz`), so coming
+    /// back to an instruction with a different accumulator isn't a loop
+    acc_steers: bool,
+    /// True once we've executed a `hlt`
+    halted: bool,
+    /// True once an instruction has tried to overflow the accumulator
+    overflowed: bool,
+}
+
+impl<'a> Machine<'a> {
+    pub fn new(program: &'a [Instruction]) -> Machine<'a> {
+        Machine {
+            program,
+            ip: 0,
+            acc: 0,
+            visited: HashSet::new(),
+            states: HashSet::new(),
+            acc_steers: program
+                .iter()
+                .any(|instruction| matches!(instruction, Instruction::Jnz(_))),
+            halted: false,
+            overflowed: false,
+        }
+    }
+
+    /// The index of the next instruction to execute
+    pub fn ip(&self) -> i64 {
+        self.ip
+    }
+
+    /// The current value of the accumulator
+    pub fn acc(&self) -> i64 {
+        self.acc
+    }
+
+    /// The program being run
+    pub fn program(&self) -> &'a [Instruction] {
+        self.program
+    }
+
+    /// The indexes of all the instructions executed so far
+    pub fn visited(&self) -> &HashSet<usize> {
+        &self.visited
+    }
+
+    /// Returns why the machine can't execute the instruction at `ip`, or None if it can
+    pub fn status(&self) -> Option<RunResult> {
+        let len = self.program.len() as i64;
+        if self.overflowed {
+            Some(RunResult::Overflowed {
+                ip: self.ip as usize,
+                acc: self.acc,
+            })
+        } else if self.halted {
+            Some(RunResult::Halted {
+                ip: self.ip as usize,
+                acc: self.acc,
+            })
+        } else if self.ip == len {
+            Some(RunResult::Terminated { acc: self.acc })
+        } else if self.ip < 0 || self.ip > len {
+            Some(RunResult::JumpedOutOfBounds {
+                ip: self.ip,
+                acc: self.acc,
+            })
+        } else if self.been_here() {
+            Some(RunResult::LoopDetected {
+                ip: self.ip as usize,
+                acc: self.acc,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// True if running on from here would repeat something we've already done
+    fn been_here(&self) -> bool {
+        let ip = self.ip as usize;
+        if self.acc_steers {
+            self.states.contains(&(ip, self.acc))
+        } else {
+            self.visited.contains(&ip)
+        }
+    }
+
+    /// Executes the instruction at `ip`.
+    /// Returns Some(result) without doing anything if the machine has stopped
+    pub fn step(&mut self) -> Option<RunResult> {
+        self.step_traced().err()
+    }
+
+    /// Executes the instruction at `ip` and records what it did.
+    /// Returns Err(result) without doing anything if the machine has stopped
+    pub fn step_traced(&mut self) -> Result<TraceEntry, RunResult> {
+        if let Some(result) = self.status() {
+            return Err(result);
+        }
+        let ip = self.ip as usize;
+        let instruction = self.program[ip];
+        let acc_before = self.acc;
+        let acc = match instruction {
+            Instruction::Acc(num) => self.acc.checked_add(num),
+            Instruction::Mul(num) => self.acc.checked_mul(num),
+            _ => Some(self.acc),
+        };
+        let acc = match acc {
+            Some(acc) => acc,
+            None => {
+                self.overflowed = true;
+                return Err(RunResult::Overflowed { ip, acc: self.acc });
+            }
+        };
+        self.visited.insert(ip);
+        self.states.insert((ip, self.acc));
+        self.acc = acc;
+        match instruction {
+            Instruction::Acc(_) | Instruction::Mul(_) | Instruction::Nop(_) => {
+                self.ip += 1;
+            }
+            Instruction::Jmp(num) => {
+                self.ip += num;
+            }
+            Instruction::Jnz(num) => {
+                self.ip += if self.acc != 0 { num } else { 1 };
+            }
+            Instruction::Hlt => {
+                self.halted = true;
+            }
+        };
+        Ok(TraceEntry {
+            ip,
+            instruction,
+            acc_before,
+            acc_after: self.acc,
+            next_ip: self.ip,
+        })
+    }
+
+    /// Keeps stepping until the machine stops
+    pub fn run(&mut self) -> RunResult {
+        loop {
+            if let Some(result) = self.step() {
+                return result;
+            }
+        }
+    }
+
+    /// Keeps stepping until the machine stops, recording every instruction executed
+    pub fn trace(&mut self) -> (Vec<TraceEntry>, RunResult) {
+        let mut entries = Vec::new();
+        loop {
+            match self.step_traced() {
+                Ok(entry) => entries.push(entry),
+                Err(result) => return (entries, result),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Machine, RunResult, TraceEntry};
+    use crate::Instruction;
+
+    #[test]
+    fn test_step() {
+        let program = vec![Instruction::Acc(3), Instruction::Jmp(-1)];
+        let mut machine = Machine::new(&program);
+        assert_eq!(machine.step(), None);
+        assert_eq!((machine.ip(), machine.acc()), (1, 3));
+        assert_eq!(machine.step(), None);
+        assert_eq!((machine.ip(), machine.acc()), (0, 3));
+        assert_eq!(
+            machine.step(),
+            Some(RunResult::LoopDetected { ip: 0, acc: 3 })
+        );
+    }
+
+    #[test]
+    fn test_trace() {
+        let program = vec![Instruction::Acc(3), Instruction::Jmp(-1)];
+        let (entries, result) = Machine::new(&program).trace();
+        let expected = vec![
+            TraceEntry {
+                ip: 0,
+                instruction: Instruction::Acc(3),
+                acc_before: 0,
+                acc_after: 3,
+                next_ip: 1,
+            },
+            TraceEntry {
+                ip: 1,
+                instruction: Instruction::Jmp(-1),
+                acc_before: 3,
+                acc_after: 3,
+                next_ip: 0,
+            },
+        ];
+        assert_eq!(entries, expected);
+        assert_eq!(result, RunResult::LoopDetected { ip: 0, acc: 3 });
+    }
+
+    #[test]
+    fn test_terminated() {
+        let program = vec![Instruction::Nop(0), Instruction::Acc(-2)];
+        assert_eq!(
+            Machine::new(&program).run(),
+            RunResult::Terminated { acc: -2 }
+        );
+    }
+
+    #[test]
+    fn test_extended_instructions() {
+        // acc = 3 * 2 - 6 is already zero, so jnz falls through to the halt
+        let program = vec![
+            Instruction::Acc(3),
+            Instruction::Mul(2),
+            Instruction::Acc(-6),
+            Instruction::Jnz(-3),
+            Instruction::Hlt,
+            Instruction::Acc(100),
+        ];
+        assert_eq!(
+            Machine::new(&program).run(),
+            RunResult::Halted { ip: 4, acc: 0 }
+        );
+    }
+
+    #[test]
+    fn test_countdown() {
+        // Comes back to `loop` with acc 2, then 1, so that's not a loop
+        let program = crate::assemble("acc +3\nloop: acc -1\njnz loop\nhlt").unwrap();
+        assert_eq!(
+            Machine::new(&program).run(),
+            RunResult::Halted { ip: 3, acc: 0 }
+        );
+        // But coming back with the same acc is
+        let program = crate::assemble("acc +3\nloop: mul 1\njnz loop\nhlt").unwrap();
+        assert_eq!(
+            Machine::new(&program).run(),
+            RunResult::LoopDetected { ip: 1, acc: 3 }
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        // No jnz, so doubling forever is a loop as soon as we're back at `loop`
+        let program = crate::assemble("acc +1\nloop: mul 2\njmp loop").unwrap();
+        assert_eq!(
+            Machine::new(&program).run(),
+            RunResult::LoopDetected { ip: 1, acc: 2 }
+        );
+        // With a jnz, acc keeps changing until it won't fit any more
+        let program = crate::assemble("acc +1\nloop: mul 2\njnz loop").unwrap();
+        let mut machine = Machine::new(&program);
+        assert_eq!(
+            machine.run(),
+            RunResult::Overflowed {
+                ip: 1,
+                acc: 1 << 62
+            }
+        );
+        assert_eq!(
+            machine.step(),
+            Some(RunResult::Overflowed {
+                ip: 1,
+                acc: 1 << 62
+            })
+        );
+    }
+
+    #[test]
+    fn test_out_of_bounds() {
+        let program = vec![Instruction::Acc(1), Instruction::Jmp(-5)];
+        assert_eq!(
+            Machine::new(&program).run(),
+            RunResult::JumpedOutOfBounds { ip: -4, acc: 1 }
+        );
+    }
+}
@@ -0,0 +1,160 @@
+//! Finds the single jmp/nop flip that makes a looping program terminate, in linear time
+//!
+//! 1. Build the control flow graph: each instruction has one successor (`jnz` has two and
+//!    `hlt` has none)
+//! 2. Walk the graph backwards from the end of the program to find every instruction that
+//!    eventually terminates
+//! 3. Walk the original program from the start. The first executed instruction whose flipped
+//!    version lands on a terminating instruction is the fix
+
+use crate::{Instruction, Machine, RunResult};
+
+/// The fix for a broken program
+#[derive(Debug, PartialEq)]
+pub struct Repair {
+    /// The index of the instruction we flipped
+    pub index: usize,
+    /// What we replaced it with
+    pub patched: Instruction,
+    /// The accumulator once the patched program terminates
+    pub acc: i64,
+}
+
+/// The instruction graph for a program
+pub struct ControlFlow {
+    /// For each instruction, the indexes of the instructions that lead to it.
+    /// The extra last entry is the end of the program
+    predecessors: Vec<Vec<usize>>,
+}
+
+impl ControlFlow {
+    pub fn new(program: &[Instruction]) -> ControlFlow {
+        let len = program.len();
+        let mut predecessors = vec![Vec::new(); len + 1];
+        for (index, instruction) in program.iter().enumerate() {
+            for next in instruction.successors(index as i64) {
+                if next >= 0 && next as usize <= len {
+                    predecessors[next as usize].push(index);
+                }
+            }
+        }
+        ControlFlow { predecessors }
+    }
+
+    /// Returns true for every instruction index that can run to the end of the program.
+    /// The extra last entry (the end of the program itself) is always true.
+    /// With `jnz` this is only a "maybe", because it depends on the accumulator
+    pub fn terminating(&self) -> Vec<bool> {
+        let end = self.predecessors.len() - 1;
+        let mut out = vec![false; end + 1];
+        let mut todo = vec![end];
+        out[end] = true;
+        while let Some(index) = todo.pop() {
+            for &previous in &self.predecessors[index] {
+                if !out[previous] {
+                    out[previous] = true;
+                    todo.push(previous);
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Finds the single jmp->nop or nop->jmp flip that makes the program terminate.
+/// Returns None if the program already terminates, or if no single flip fixes it
+pub fn find_repair(program: &[Instruction]) -> Option<Repair> {
+    if let RunResult::Terminated { .. } = Machine::new(program).run() {
+        return None;
+    }
+    let terminating = ControlFlow::new(program).terminating();
+    let lands_on_terminating =
+        |next: i64| next >= 0 && (next as usize) < terminating.len() && terminating[next as usize];
+    // Walk the original program until it loops, halts, or jumps away
+    let mut machine = Machine::new(program);
+    while let Ok(entry) = machine.step_traced() {
+        let patched = match entry.instruction.flipped() {
+            Some(patched) => patched,
+            None => continue,
+        };
+        if !patched
+            .successors(entry.ip as i64)
+            .into_iter()
+            .any(lands_on_terminating)
+        {
+            continue;
+        }
+        let mut program = program.to_vec();
+        program[entry.ip] = patched;
+        // Only `jnz` can make the analysis wrong, so check by running it
+        if let RunResult::Terminated { acc } = Machine::new(&program).run() {
+            return Some(Repair {
+                index: entry.ip,
+                patched,
+                acc,
+            });
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Repair;
+    use crate::{parse, Instruction};
+
+    #[test]
+    fn test_jmp_to_nop() {
+        let program = parse(
+            r#"nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6"#,
+        );
+        let expected = Some(Repair {
+            index: 7,
+            patched: Instruction::Nop(-4),
+            acc: 8,
+        });
+        assert_eq!(super::find_repair(&program), expected);
+    }
+
+    #[test]
+    fn test_nop_to_jmp() {
+        let program = parse(
+            r#"acc +2
+nop +3
+jmp -2
+acc +100
+acc +5"#,
+        );
+        let expected = Some(Repair {
+            index: 1,
+            patched: Instruction::Jmp(3),
+            acc: 7,
+        });
+        assert_eq!(super::find_repair(&program), expected);
+    }
+
+    #[test]
+    fn test_mul_loop() {
+        let program = crate::assemble("acc +1\nloop: mul 2\njmp loop").unwrap();
+        let expected = Some(Repair {
+            index: 2,
+            patched: Instruction::Nop(-1),
+            acc: 2,
+        });
+        assert_eq!(super::find_repair(&program), expected);
+    }
+
+    #[test]
+    fn test_already_terminates() {
+        let program = parse("acc +1\nnop +0");
+        assert_eq!(super::find_repair(&program), None);
+    }
+}
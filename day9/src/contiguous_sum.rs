@@ -1,31 +1,188 @@
-/// Searches through `input` to find a contiguous list of numbers that add up to `query`
+//! Finds contiguous ranges of numbers with a given sum
+
+use std::ops::Range;
+
+/// A contiguous range of the input that adds up to the query
+#[derive(Debug, PartialEq, Clone)]
+pub struct ContiguousRange {
+    /// Where the numbers are in the input
+    pub range: Range<usize>,
+    /// The sum of all the numbers in the range
+    pub sum: usize,
+    /// The smallest number in the range
+    pub min: usize,
+    /// The largest number in the range
+    pub max: usize,
+}
+
+/// Lazily finds every contiguous range that adds up to the query, ordered by where the range ends
+///
+/// Because all the numbers are positive (or zero), the possible starts for each end only ever
+/// move forward, so two pointers into the running sums find them all in linear time
+/// (plus the size of the output)
+pub struct ContiguousRanges<'a> {
+    input: &'a [usize],
+    query: usize,
+    min_len: usize,
+    /// prefix[i] is the sum of input[..i]
+    prefix: Vec<usize>,
+    /// The end (exclusive) of the ranges we're looking at
+    end: usize,
+    /// The first start whose range sum is <= query
+    lo: usize,
+    /// The first start whose range sum is < query
+    hi: usize,
+    /// Matching starts for the current end that we haven't returned yet
+    pending: Range<usize>,
+}
+
+impl<'a> ContiguousRanges<'a> {
+    fn make(&self, range: Range<usize>) -> ContiguousRange {
+        let numbers = &self.input[range.clone()];
+        ContiguousRange {
+            sum: self.prefix[range.end] - self.prefix[range.start],
+            min: numbers.iter().cloned().min().unwrap_or(0),
+            max: numbers.iter().cloned().max().unwrap_or(0),
+            range,
+        }
+    }
+}
+
+impl<'a> Iterator for ContiguousRanges<'a> {
+    type Item = ContiguousRange;
+
+    fn next(&mut self) -> Option<ContiguousRange> {
+        loop {
+            if let Some(start) = self.pending.next() {
+                return Some(self.make(start..self.end));
+            }
+            self.end += 1;
+            if self.end > self.input.len() {
+                return None;
+            }
+            // We want starts where prefix[start] == prefix[end] - query
+            let target = match self.prefix[self.end].checked_sub(self.query) {
+                Some(target) => target,
+                None => continue,
+            };
+            while self.prefix[self.lo] < target {
+                self.lo += 1;
+            }
+            self.hi = self.hi.max(self.lo);
+            while self.hi < self.end && self.prefix[self.hi] <= target {
+                self.hi += 1;
+            }
+            // Ranges must be at least `min_len` long
+            let last_start = (self.end + 1).saturating_sub(self.min_len);
+            self.pending = self.lo..self.hi.min(last_start);
+        }
+    }
+}
+
+/// Returns every contiguous range of at least `min_len` numbers in `input` that adds up to `query`
+pub fn contiguous_ranges(input: &[usize], query: usize, min_len: usize) -> ContiguousRanges<'_> {
+    let mut prefix = Vec::with_capacity(input.len() + 1);
+    prefix.push(0);
+    input.iter().fold(0, |sum, num| {
+        prefix.push(sum + num);
+        sum + num
+    });
+    ContiguousRanges {
+        input,
+        query,
+        // Empty ranges are never interesting
+        min_len: min_len.max(1),
+        prefix,
+        end: 0,
+        lo: 0,
+        hi: 0,
+        pending: 0..0,
+    }
+}
+
+/// Returns the first contiguous range (by where it ends) of at least `min_len` numbers in `input`
+/// that adds up to `query`
+pub fn find_contiguous_range(
+    input: &[usize],
+    query: usize,
+    min_len: usize,
+) -> Option<ContiguousRange> {
+    contiguous_ranges(input, query, min_len).next()
+}
+
+/// Searches through `input` to find a contiguous list of at least two numbers that add up to `query`
 /// Returns the *sum* of the *smallest* (min) and *largest* (max) of that contiguous list of numbers
 /// If no such range is found, returns None
 pub fn contiguous_sum(input: &[usize], query: usize) -> Option<usize> {
-    (0..input.len())
-        .flat_map(|start| {
-            let mut sum = 0;
-            let mut end = start;
-            while let Some(num) = input.get(end) {
-                sum += num;
-                // We found our contiguous range, return the start and end of the range
-                if sum == query {
-                    return Some(start..end + 1);
-                } else if sum > query {
-                    // We went over the sum, this index doesn't work
-                    return None;
-                } else {
-                    // Make the range a bit bigger
-                    end += 1;
-                }
+    find_contiguous_range(input, query, 2).map(|range| range.min + range.max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ContiguousRange;
+
+    fn input() -> Vec<usize> {
+        vec![
+            35, 20, 15, 25, 47, 40, 62, 55, 65, 95, 102, 117, 150, 182, 127, 219, 299, 277, 309,
+            576,
+        ]
+    }
+
+    #[test]
+    fn test_contiguous_sum() {
+        assert_eq!(super::contiguous_sum(&input(), 127), Some(62));
+    }
+
+    #[test]
+    fn test_find_range() {
+        let expected = Some(ContiguousRange {
+            range: 2..6,
+            sum: 127,
+            min: 15,
+            max: 47,
+        });
+        assert_eq!(super::find_contiguous_range(&input(), 127, 2), expected);
+    }
+
+    #[test]
+    fn test_min_len() {
+        // 47 is only in the input by itself
+        let input = input();
+        assert_eq!(super::find_contiguous_range(&input, 47, 2), None);
+        let got = super::find_contiguous_range(&input, 47, 1).map(|range| range.range);
+        assert_eq!(got, Some(4..5));
+        // 15 + 25 + 47 + 40 is the only way to make 127 with 4 or more numbers
+        let got: Vec<_> = super::contiguous_ranges(&input, 127, 4)
+            .map(|range| range.range)
+            .collect();
+        assert_eq!(got, vec![2..6]);
+    }
+
+    #[test]
+    fn test_all_ranges() {
+        let input = vec![1, 2, 3, 0, 3, 3];
+        let got: Vec<_> = super::contiguous_ranges(&input, 3, 1)
+            .map(|range| range.range)
+            .collect();
+        let expected = vec![0..2, 2..3, 2..4, 3..5, 4..5, 5..6];
+        assert_eq!(got, expected);
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let input = vec![0, 4, 1, 0, 0, 3, 2, 2, 0, 5, 1];
+        for query in 0..12 {
+            for min_len in 1..4 {
+                let mut expected: Vec<_> = (0..input.len())
+                    .flat_map(|start| (start + min_len..=input.len()).map(move |end| start..end))
+                    .filter(|range| input[range.clone()].iter().sum::<usize>() == query)
+                    .collect();
+                expected.sort_by_key(|range| (range.end, range.start));
+                let got: Vec<_> = super::contiguous_ranges(&input, query, min_len)
+                    .map(|range| range.range)
+                    .collect();
+                assert_eq!(expected, got, "query: {} min_len: {}", query, min_len);
             }
-            // No ranges got >= query
-            None
-        })
-        .flat_map(|range| {
-            let min = input[range.clone()].iter().min().cloned();
-            let max = input[range].iter().max().cloned();
-            min.zip(max).map(|(a, b)| a + b)
-        })
-        .next()
+        }
+    }
 }
@@ -2,7 +2,9 @@ mod parser;
 pub use parser::parse;
 
 mod validator;
-pub use validator::validate;
+pub use validator::{invalid_numbers, validate, Invalid, InvalidNumbers};
 
 mod contiguous_sum;
-pub use contiguous_sum::contiguous_sum;
+pub use contiguous_sum::{
+    contiguous_ranges, contiguous_sum, find_contiguous_range, ContiguousRange, ContiguousRanges,
+};
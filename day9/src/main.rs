@@ -14,25 +14,42 @@
 //!  * All numbers in the preamble are valid
 //!  * Any subsequent number must be the sum of 2 numbers `preamble length` in the past
 //!    + eg. The 20th number, with a preamble length of 5, must be the sum of any two numbers in the range 14-19
+//!
+//! ## Usage
+//!
+//! `day9 [preamble length]` - the preamble length defaults to 25
 
-use std::fs::read_to_string;
+use std::{env, fs::read_to_string};
 
-use day9::{contiguous_sum, parse, validate};
+use day9::{find_contiguous_range, invalid_numbers, parse, ContiguousRange, Invalid};
 
 fn main() {
+    let preamble_length = env::args()
+        .nth(1)
+        .map(|arg| arg.parse().expect("Preamble length must be a number"))
+        .unwrap_or(25);
     let input = read_to_string("input.txt").expect("Unable to read input.txt");
     let input = parse(&input);
-    let part_1 = validate(&input, 25);
-    if let Some(part_1) = part_1 {
+    let invalid: Vec<Invalid> = invalid_numbers(input.iter().cloned(), preamble_length).collect();
+    for Invalid { index, value } in &invalid {
+        println!("Invalid number at line {}: {}", index + 1, value);
+    }
+    if let Some(part_1) = invalid.first().map(|invalid| invalid.value) {
         println!("Day 9 - Part 1: {:?}", part_1);
         // Part 2
-        let part_2 = contiguous_sum(&input, part_1);
-        println!(
-            "Day 9 - Part 2: {}",
-            part_2
-                .map(|num| format!("{}", num))
-                .unwrap_or_else(|| "No answer found".to_string())
-        );
+        match find_contiguous_range(&input, part_1, 2) {
+            Some(ContiguousRange {
+                range, min, max, ..
+            }) => println!(
+                "Day 9 - Part 2: {} (lines {} to {}, min {}, max {})",
+                min + max,
+                range.start + 1,
+                range.end,
+                min,
+                max
+            ),
+            None => println!("Day 9 - Part 2: No answer found"),
+        }
     } else {
         println!("No answers found")
     }
@@ -9,7 +9,7 @@ pub fn parse(input: &str) -> Vec<usize> {
         .filter(|(_index, line)| !line.is_empty())
         .map(|(index, line)| {
             line.parse()
-                .expect(&format!("Unable to parse line {}: {}", index + 1, line))
+                .unwrap_or_else(|_| panic!("Unable to parse line {}: {}", index + 1, line))
         })
         .collect()
 }
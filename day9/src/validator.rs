@@ -1,6 +1,114 @@
 //! Validates a list of numbers
 
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
+
+/// A number that isn't the sum of two numbers in the preamble before it
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Invalid {
+    /// Where the number is in the input (0 based)
+    pub index: usize,
+    /// The number itself
+    pub value: usize,
+}
+
+/// The last `n` numbers seen, kept as both a queue (for eviction) and a multiset (for lookups)
+struct Window {
+    order: VecDeque<usize>,
+    counts: HashMap<usize, usize>,
+}
+
+impl Window {
+    fn with_capacity(n: usize) -> Window {
+        Window {
+            order: VecDeque::with_capacity(n + 1),
+            counts: HashMap::with_capacity(n),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    fn push(&mut self, num: usize) {
+        self.order.push_back(num);
+        *self.counts.entry(num).or_default() += 1;
+    }
+
+    fn pop(&mut self) {
+        if let Some(num) = self.order.pop_front() {
+            if let Some(count) = self.counts.get_mut(&num) {
+                *count -= 1;
+                if *count == 0 {
+                    self.counts.remove(&num);
+                }
+            }
+        }
+    }
+
+    /// Returns true if `num` is the sum of two different entries. O(n)
+    fn has_pair_summing_to(&self, num: usize) -> bool {
+        self.counts.iter().any(|(&x, &count)| {
+            x <= num && {
+                let y = num - x;
+                if x == y {
+                    count >= 2
+                } else {
+                    self.counts.contains_key(&y)
+                }
+            }
+        })
+    }
+}
+
+/// Lazily finds every invalid number in a (possibly endless) stream of numbers
+///
+/// Each number after the `n`'th should be the sum of 2 numbers up to `n` back to be valid
+pub struct InvalidNumbers<I> {
+    input: I,
+    window: Window,
+    n: usize,
+    index: usize,
+}
+
+impl<I: Iterator<Item = usize>> Iterator for InvalidNumbers<I> {
+    type Item = Invalid;
+
+    fn next(&mut self) -> Option<Invalid> {
+        for value in &mut self.input {
+            let index = self.index;
+            self.index += 1;
+            // The preamble is always valid
+            let is_valid = self.window.len() < self.n || self.window.has_pair_summing_to(value);
+            // Every number becomes part of the window, valid or not
+            self.window.push(value);
+            if self.window.len() > self.n {
+                self.window.pop();
+            }
+            if !is_valid {
+                return Some(Invalid { index, value });
+            }
+        }
+        None
+    }
+}
+
+/// Returns an iterator over every invalid number in `input`.
+///
+/// ## Arguments
+///
+///  * input: the numbers to validate; can be endless
+///  * n: the number/length of the preamble
+pub fn invalid_numbers<I: IntoIterator<Item = usize>>(
+    input: I,
+    n: usize,
+) -> InvalidNumbers<I::IntoIter> {
+    InvalidNumbers {
+        input: input.into_iter(),
+        window: Window::with_capacity(n),
+        n,
+        index: 0,
+    }
+}
 
 /// Returns the value of the first invalid number if any.
 ///
@@ -11,43 +119,79 @@ use std::collections::VecDeque;
 ///  * input: the list of numbers to validate
 ///  * n: the number/length of the preamble
 pub fn validate(input: &[usize], n: usize) -> Option<usize> {
-    // Our ring buffer of previous `n` entries
-    let mut preamble: VecDeque<usize> = input.iter().take(n).cloned().collect();
-    input.iter().skip(n).cloned().find(|num| {
-        // Check if this number is a combination of two entries in our preamble
-        let is_valid = (0..n)
-            // Go through every combination of entries
-            .flat_map(|x| (0..n).map(move |y| (x, y)))
-            // Exclude duplicate entries
-            .filter(|(x, y)| x != y)
-            // Get the sum of the two numbers from the preamble
-            .map(|(x, y)| preamble[x] + preamble[y])
-            // If any sum matches, the number is valid
-            .any(|sum| sum == *num);
-        // But we're looking for the first *invalid* number
-        if is_valid {
-            // Update preamble and continue
-            preamble.pop_front();
-            preamble.push_back(*num);
-            false
-        } else {
-            // We found the bad number - exit
-            true
-        }
-    })
+    invalid_numbers(input.iter().cloned(), n)
+        .next()
+        .map(|invalid| invalid.value)
 }
 
 #[cfg(test)]
 mod tests {
+    use super::Invalid;
 
-    #[test]
-    fn test_validate() {
-        let input = vec![
+    fn input() -> Vec<usize> {
+        vec![
             35, 20, 15, 25, 47, 40, 62, 55, 65, 95, 102, 117, 150, 182, 127, 219, 299, 277, 309,
             576,
-        ];
+        ]
+    }
+
+    #[test]
+    fn test_validate() {
+        let input = input();
         let expected = Some(127);
         let got = super::validate(&input, 5);
         assert_eq!(expected, got);
     }
+
+    #[test]
+    fn test_all_invalid() {
+        let got: Vec<Invalid> = super::invalid_numbers(input(), 5).collect();
+        let expected = vec![Invalid {
+            index: 14,
+            value: 127,
+        }];
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let input = input();
+        for n in 2..8 {
+            // Check every ordered pair of the previous `n` numbers
+            let expected: Vec<usize> = (n..input.len())
+                .filter(|&i| {
+                    let preamble = &input[i - n..i];
+                    !(0..n)
+                        .flat_map(|x| (0..n).map(move |y| (x, y)))
+                        .any(|(x, y)| x != y && preamble[x] + preamble[y] == input[i])
+                })
+                .collect();
+            let got: Vec<usize> = super::invalid_numbers(input.clone(), n)
+                .map(|invalid| invalid.index)
+                .collect();
+            assert_eq!(expected, got, "Preamble length: {}", n);
+        }
+    }
+
+    #[test]
+    fn test_duplicates() {
+        // 10 = 5 + 5 only works while there are two 5s in the window
+        let got: Vec<usize> = super::invalid_numbers(vec![5, 5, 1, 10, 2, 10], 3)
+            .map(|invalid| invalid.index)
+            .collect();
+        assert_eq!(got, vec![4, 5]);
+    }
+
+    #[test]
+    fn test_endless_input() {
+        // 0 + 0 = 0 forever, until a 3 turns up
+        let got = super::invalid_numbers((0..).map(|i| if i == 1000 { 3 } else { 0 }), 10).next();
+        assert_eq!(
+            got,
+            Some(Invalid {
+                index: 1000,
+                value: 3
+            })
+        );
+    }
 }